@@ -12,50 +12,174 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::Result;
+use std::sync::mpsc::Receiver;
 
+use crate::evaluation::EvaluatedConfiguration;
+use crate::{Entity, Error, Result};
+
+use crate::client::evaluated_bootstrap::EvaluatedBootstrap;
 use crate::client::feature_proxy::FeatureProxy;
 use crate::client::feature_snapshot::FeatureSnapshot;
 use crate::client::property_proxy::PropertyProxy;
 use crate::client::property_snapshot::PropertySnapshot;
+use crate::client::subscriptions::{ChangeCallback, ConfigurationChange, SubscriptionFilter};
 
 /// AppConfiguration client for browsing, and evaluating features and properties.
 pub trait AppConfigurationClient {
     /// Returns the list of features.
-    /// 
+    ///
     /// The list contains the `id`s that can be used in [`get_feature`](AppConfigurationClient::get_feature)
     /// or [`get_feature_proxy`](AppConfigurationClient::get_feature_proxy) to retrieve the actual features.
     fn get_feature_ids(&self) -> Result<Vec<String>>;
 
     /// Returns a snapshot for a [`Feature`](crate::Feature).
-    /// 
+    ///
     /// The instance contains a snapshot with all the values and rules, so it
     /// will always evaluate the same entities to the same values, no updates
     /// will be received from the server.
     fn get_feature(&self, feature_id: &str) -> Result<FeatureSnapshot>;
 
     /// Returns a proxied [`Feature`](crate::Feature).
-    /// 
+    ///
     /// This proxied feature will envaluate entities using the latest information
     /// available if the client implementation support some kind of live-updates.
     fn get_feature_proxy<'a>(&'a self, feature_id: &str) -> Result<FeatureProxy<'a>>;
 
     /// Returns the list of properties.
-    /// 
+    ///
     /// The list contains the `id`s that can be used in [`get_property`](AppConfigurationClient::get_property)
     /// or [`get_property_proxy`](AppConfigurationClient::get_property_proxy) to retrieve the actual features.
     fn get_property_ids(&self) -> Result<Vec<String>>;
 
     /// Returns a snapshot for a [`Property`](crate::Property).
-    /// 
+    ///
     /// The instance contains a snapshot with all the values and rules, so it
     /// will always evaluate the same entities to the same values, no updates
     /// will be received from the server
     fn get_property(&self, property_id: &str) -> Result<PropertySnapshot>;
 
     /// Returns a proxied [`Property`](crate::Property).
-    /// 
+    ///
     /// This proxied property will envaluate entities using the latest information
     /// available if the client implementation support some kind of live-updates.
-    fn get_property_proxy(&self, property_id: &str) -> Result<PropertyProxy>;
+    fn get_property_proxy(&self, property_id: &str) -> Result<PropertyProxy<'_>>;
+
+    /// Evaluates all the features and properties for the given [`Entity`].
+    ///
+    /// The clients in this crate evaluate all of them against the same configuration, even
+    /// if they receive an update meanwhile. The default implementation evaluates them one
+    /// by one, using [`get_feature`](AppConfigurationClient::get_feature) and
+    /// [`get_property`](AppConfigurationClient::get_property).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use appconfiguration::{AppConfigurationClient, Result, Entity};
+    /// # fn doctest_evaluate_all(client: impl AppConfigurationClient, entity: &impl Entity) -> Result<()> {
+    ///     let evaluated = client.evaluate_all(entity)?;
+    ///     for (feature_id, value) in evaluated.feature_values() {
+    ///         println!("Feature '{feature_id}' has value {value:?}");
+    ///     }
+    ///     println!("Reason for 'my_feature': {:?}", evaluated.features["my_feature"].reason);
+    /// #   Ok(())
+    /// # }
+    /// ```
+    fn evaluate_all(&self, entity: &impl Entity) -> Result<EvaluatedConfiguration>
+    where
+        Self: Sized,
+    {
+        let mut evaluated = EvaluatedConfiguration::default();
+        for feature_id in self.get_feature_ids()? {
+            match self
+                .get_feature(&feature_id)
+                .and_then(|feature| feature.evaluate_details(entity))
+            {
+                Ok(details) => {
+                    evaluated.features.insert(feature_id, details);
+                }
+                Err(e) => {
+                    evaluated.feature_errors.insert(feature_id, e);
+                }
+            }
+        }
+        for property_id in self.get_property_ids()? {
+            match self
+                .get_property(&property_id)
+                .and_then(|property| property.evaluate_details(entity))
+            {
+                Ok(details) => {
+                    evaluated.properties.insert(property_id, details);
+                }
+                Err(e) => {
+                    evaluated.property_errors.insert(property_id, e);
+                }
+            }
+        }
+        Ok(evaluated)
+    }
+
+    /// Evaluates all the features and properties for the given [`Entity`] and returns
+    /// them as an [`EvaluatedBootstrap`] document.
+    ///
+    /// The document can be serialized and sent to client-side applications, where
+    /// [`AppConfigurationEvaluated`](crate::AppConfigurationEvaluated) gives access to the values.
+    ///
+    /// The document includes the version of the configuration, so clients that don't know it
+    /// return an error (the default implementation).
+    fn evaluate_bootstrap(&self, entity: &impl Entity) -> Result<EvaluatedBootstrap>
+    where
+        Self: Sized,
+    {
+        let _ = entity;
+        Err(Error::Other(
+            "This client does not support evaluated bootstraps".to_string(),
+        ))
+    }
+
+    /// Subscribes to changes in the configuration.
+    ///
+    /// Every time the client replaces its configuration, the changes to the features and
+    /// properties matching the `filter` are sent through the returned channel. The
+    /// subscription is cancelled when the receiver is dropped.
+    ///
+    /// Clients not receiving updates return an error (the default implementation).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use appconfiguration::{AppConfigurationClient, Result, SubscriptionFilter};
+    /// # fn doctest_subscribe(client: impl AppConfigurationClient) -> Result<()> {
+    ///     let changes = client.subscribe(SubscriptionFilter::default().property("pool_size"))?;
+    ///     std::thread::spawn(move || {
+    ///         for change in changes {
+    ///             println!("Property 'pool_size' changed: {:?}", change.properties["pool_size"]);
+    ///         }
+    ///     });
+    /// #   Ok(())
+    /// # }
+    /// ```
+    fn subscribe(&self, filter: SubscriptionFilter) -> Result<Receiver<ConfigurationChange>> {
+        let _ = filter;
+        Err(Error::Other(
+            "This client does not support subscriptions".to_string(),
+        ))
+    }
+
+    /// Subscribes a callback to changes in the configuration.
+    ///
+    /// The callback is invoked from the thread (or task) receiving the updates from the server,
+    /// so it should return quickly. It is kept for the lifetime of the client. The callback can
+    /// query the client and make new subscriptions.
+    ///
+    /// Clients not receiving updates return an error (the default implementation).
+    fn subscribe_with_callback(
+        &self,
+        filter: SubscriptionFilter,
+        callback: ChangeCallback,
+    ) -> Result<()> {
+        let _ = (filter, callback);
+        Err(Error::Other(
+            "This client does not support subscriptions".to_string(),
+        ))
+    }
 }
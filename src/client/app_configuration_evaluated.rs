@@ -0,0 +1,158 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::de::DeserializeOwned;
+
+use crate::client::evaluated_bootstrap::{EvaluatedBootstrap, EvaluatedFeature, EvaluatedProperty};
+use crate::errors::{ConfigurationAccessError, Error, Result};
+use crate::{Entity, Feature, Property, Value};
+
+/// Read-only client over an [`EvaluatedBootstrap`] document.
+///
+/// Values are not evaluated again: they are the ones computed for the entity the
+/// document was created for, and asking for them with a different entity is an error.
+#[derive(Debug)]
+pub struct AppConfigurationEvaluated {
+    bootstrap: EvaluatedBootstrap,
+}
+
+impl AppConfigurationEvaluated {
+    /// Creates a client reading the values from `bootstrap`.
+    pub fn new(bootstrap: EvaluatedBootstrap) -> Self {
+        Self { bootstrap }
+    }
+
+    /// Creates a client from an [`EvaluatedBootstrap`] serialized as JSON.
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        let bootstrap = serde_json::from_str(json).map_err(Error::ConfigurationParseError)?;
+        Ok(Self::new(bootstrap))
+    }
+
+    /// Returns the version of the configuration the values were evaluated with.
+    pub fn get_version(&self) -> &str {
+        &self.bootstrap.version
+    }
+
+    /// Returns the list of features.
+    pub fn get_feature_ids(&self) -> Vec<String> {
+        self.bootstrap.features.keys().cloned().collect()
+    }
+
+    /// Returns the evaluated [`Feature`] with the given id.
+    pub fn get_feature(&self, feature_id: &str) -> Result<EvaluatedFeatureSnapshot> {
+        let feature = self.bootstrap.features.get(feature_id).ok_or_else(|| {
+            ConfigurationAccessError::FeatureNotFound {
+                feature_id: feature_id.to_string(),
+            }
+        })?;
+        Ok(EvaluatedFeatureSnapshot {
+            entity_id: self.bootstrap.entity_id.clone(),
+            feature: feature.clone(),
+        })
+    }
+
+    /// Returns the list of properties.
+    pub fn get_property_ids(&self) -> Vec<String> {
+        self.bootstrap.properties.keys().cloned().collect()
+    }
+
+    /// Returns the evaluated [`Property`] with the given id.
+    pub fn get_property(&self, property_id: &str) -> Result<EvaluatedPropertySnapshot> {
+        let property = self.bootstrap.properties.get(property_id).ok_or_else(|| {
+            ConfigurationAccessError::PropertyNotFound {
+                property_id: property_id.to_string(),
+            }
+        })?;
+        Ok(EvaluatedPropertySnapshot {
+            entity_id: self.bootstrap.entity_id.clone(),
+            property: property.clone(),
+        })
+    }
+}
+
+fn check_entity(expected_id: &str, entity: &impl Entity) -> Result<()> {
+    let entity_id = entity.get_id();
+    if entity_id == expected_id {
+        Ok(())
+    } else {
+        Err(Error::EntityMismatch {
+            expected_id: expected_id.to_string(),
+            entity_id,
+        })
+    }
+}
+
+/// A [`Feature`] from an [`EvaluatedBootstrap`].
+#[derive(Debug)]
+pub struct EvaluatedFeatureSnapshot {
+    entity_id: String,
+    feature: EvaluatedFeature,
+}
+
+impl Feature for EvaluatedFeatureSnapshot {
+    fn get_name(&self) -> Result<String> {
+        Ok(self.feature.name.clone())
+    }
+
+    fn is_enabled(&self) -> Result<bool> {
+        Ok(self.feature.enabled)
+    }
+
+    fn get_value(&self, entity: &impl Entity) -> Result<Value> {
+        check_entity(&self.entity_id, entity)?;
+        Ok(self.feature.value.clone())
+    }
+
+    fn get_value_into<T: TryFrom<Value, Error = crate::Error>>(
+        &self,
+        entity: &impl Entity,
+    ) -> Result<T> {
+        let value = self.get_value(entity)?;
+        value.try_into()
+    }
+
+    fn get_value_as<T: DeserializeOwned>(&self, entity: &impl Entity) -> Result<T> {
+        self.get_value(entity)?.deserialize_into()
+    }
+}
+
+/// A [`Property`] from an [`EvaluatedBootstrap`].
+#[derive(Debug)]
+pub struct EvaluatedPropertySnapshot {
+    entity_id: String,
+    property: EvaluatedProperty,
+}
+
+impl Property for EvaluatedPropertySnapshot {
+    fn get_name(&self) -> Result<String> {
+        Ok(self.property.name.clone())
+    }
+
+    fn get_value(&self, entity: &impl Entity) -> Result<Value> {
+        check_entity(&self.entity_id, entity)?;
+        Ok(self.property.value.clone())
+    }
+
+    fn get_value_into<T: TryFrom<Value, Error = crate::Error>>(
+        &self,
+        entity: &impl Entity,
+    ) -> Result<T> {
+        let value = self.get_value(entity)?;
+        value.try_into()
+    }
+
+    fn get_value_as<T: DeserializeOwned>(&self, entity: &impl Entity) -> Result<T> {
+        self.get_value(entity)?.deserialize_into()
+    }
+}
@@ -12,36 +12,38 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::client::cache::ConfigurationSnapshot;
+use crate::client::context::ConnectionContext;
+use crate::client::evaluated_bootstrap::EvaluatedBootstrap;
 pub use crate::client::feature_proxy::FeatureProxy;
 use crate::client::feature_snapshot::FeatureSnapshot;
-use crate::client::http;
+use crate::client::http::HttpOptions;
+use crate::client::monitor::{ClientState, Monitor};
+use crate::client::options::ClientOptions;
 pub use crate::client::property_proxy::PropertyProxy;
 use crate::client::property_snapshot::PropertySnapshot;
-use crate::errors::{ConfigurationAccessError, Error, Result};
-use crate::models::Segment;
-use std::collections::{HashMap, HashSet};
-use std::net::TcpStream;
-use std::sync::{Arc, Mutex};
+use crate::client::status::ConnectionState;
+use crate::client::subscriptions::{ChangeCallback, ConfigurationChange, SubscriptionFilter};
+use crate::client::transport::{HttpTransport, Transport, UpdateStream};
+use crate::errors::Result;
+use crate::evaluation::EvaluatedConfiguration;
+use crate::Entity;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, TryRecvError};
+use std::sync::Arc;
 use std::thread;
-
-use tungstenite::stream::MaybeTlsStream;
-use tungstenite::Message;
-use tungstenite::WebSocket;
+use std::time::{Duration, SystemTime};
 
 use super::AppConfigurationClient;
 
 /// AppConfiguration client connection to IBM Cloud.
 #[derive(Debug)]
 pub struct AppConfigurationClientIBMCloud {
-    pub(crate) latest_config_snapshot: Arc<Mutex<ConfigurationSnapshot>>,
+    pub(crate) state: ClientState,
     pub(crate) _thread_terminator: std::sync::mpsc::Sender<()>,
 }
 
 impl AppConfigurationClientIBMCloud {
-
     /// Creates a new [`AppConfigurationClient`] connecting to IBM Cloud.
-    /// 
+    ///
     /// This client keeps a websocket open to the server to receive live-updates
     /// to features and properties.
     ///
@@ -59,208 +61,153 @@ impl AppConfigurationClientIBMCloud {
         environment_id: &str,
         collection_id: &str,
     ) -> Result<Self> {
-        let access_token = http::get_access_token(apikey)?;
-
-        // Populate initial configuration
-        let latest_config_snapshot: Arc<Mutex<ConfigurationSnapshot>> =
-            Arc::new(Mutex::new(Self::get_configuration_snapshot(
-                &access_token,
-                region,
-                guid,
-                environment_id,
-                collection_id,
-            )?));
-
-        // start monitoring configuration
-        let terminator = Self::update_cache_in_background(
-            latest_config_snapshot.clone(),
+        Self::new_with_options(
             apikey,
             region,
             guid,
             environment_id,
             collection_id,
-        )?;
-
-        let client = AppConfigurationClientIBMCloud {
-            latest_config_snapshot,
-            _thread_terminator: terminator,
-        };
-
-        Ok(client)
+            ClientOptions::default(),
+        )
     }
 
-    fn get_configuration_snapshot(
-        access_token: &str,
+    /// Creates a new [`AppConfigurationClient`] connecting to IBM Cloud using
+    /// the given [`ClientOptions`].
+    ///
+    /// See [`AppConfigurationClientIBMCloud::new`] for a description of the arguments. Invalid
+    /// options are reported as a [`BuilderError`](crate::BuilderError).
+    pub fn new_with_options(
+        apikey: &str,
         region: &str,
         guid: &str,
         environment_id: &str,
         collection_id: &str,
-    ) -> Result<ConfigurationSnapshot> {
-        let configuration = http::get_configuration(
-            // TODO: access_token might expire. This will cause issues with long-running apps
-            access_token,
+        options: ClientOptions,
+    ) -> Result<Self> {
+        let transport = HttpTransport::new(HttpOptions::new(&options));
+        Self::new_with_transport(
+            apikey,
             region,
             guid,
-            collection_id,
             environment_id,
-        )?;
-        ConfigurationSnapshot::new(environment_id, configuration)
-    }
-
-    fn wait_for_configuration_update(
-        socket: &mut WebSocket<MaybeTlsStream<TcpStream>>,
-        access_token: &str,
-        region: &str,
-        guid: &str,
-        collection_id: &str,
-        environment_id: &str,
-    ) -> Result<ConfigurationSnapshot> {
-        loop {
-            // read() blocks until something happens.
-            match socket.read()? {
-                Message::Text(text) => match text.as_str() {
-                    "test message" => {} // periodically sent by the server
-                    _ => {
-                        return Self::get_configuration_snapshot(
-                            access_token,
-                            region,
-                            guid,
-                            environment_id,
-                            collection_id,
-                        );
-                    }
-                },
-                Message::Close(_) => {
-                    return Err(Error::Other("Connection closed by the server".into()));
-                }
-                _ => {}
-            }
-        }
-    }
-
-    fn update_configuration_on_change(
-        mut socket: WebSocket<MaybeTlsStream<TcpStream>>,
-        latest_config_snapshot: Arc<Mutex<ConfigurationSnapshot>>,
-        access_token: String,
-        region: String,
-        guid: String,
-        collection_id: String,
-        environment_id: String,
-    ) -> std::sync::mpsc::Sender<()> {
-        let (sender, receiver) = std::sync::mpsc::channel();
-
-        thread::spawn(move || {
-            loop {
-                // If the sender has gone (AppConfiguration instance is dropped), then finish this thread
-                if let Err(e) = receiver.try_recv() {
-                    if e == std::sync::mpsc::TryRecvError::Disconnected {
-                        break;
-                    }
-                }
-
-                let config_snapshot = Self::wait_for_configuration_update(
-                    &mut socket,
-                    &access_token,
-                    &region,
-                    &guid,
-                    &collection_id,
-                    &environment_id,
-                );
-
-                match config_snapshot {
-                    Ok(config_snapshot) => *latest_config_snapshot.lock()? = config_snapshot,
-                    Err(e) => {
-                        println!("Waiting for configuration update failed. Stopping to monitor for changes.: {e}");
-                        break;
-                    }
-                }
-            }
-            Ok::<(), Error>(())
-        });
-
-        sender
+            collection_id,
+            options,
+            transport,
+        )
     }
 
-    fn update_cache_in_background(
-        latest_config_snapshot: Arc<Mutex<ConfigurationSnapshot>>,
+    /// Creates a new [`AppConfigurationClient`] that uses the given [`Transport`] to
+    /// talk to the server.
+    ///
+    /// The HTTP settings in [`ClientOptions`] (timeouts and proxy) are not used, they
+    /// are up to the `transport`. See [`AppConfigurationClientIBMCloud::new`] for a
+    /// description of the other arguments.
+    pub fn new_with_transport<T: Transport>(
         apikey: &str,
         region: &str,
         guid: &str,
         environment_id: &str,
         collection_id: &str,
-    ) -> Result<std::sync::mpsc::Sender<()>> {
-        let access_token = http::get_access_token(apikey)?;
-        let (socket, _response) = http::get_configuration_monitoring_websocket(
-            &access_token,
+        options: ClientOptions,
+        transport: T,
+    ) -> Result<Self> {
+        options.validate()?;
+        let context = Arc::new(ConnectionContext::new(
+            apikey,
             region,
             guid,
-            collection_id,
             environment_id,
-        )?;
+            collection_id,
+            options,
+            transport,
+        ));
+
+        // Populate initial configuration, using the local copies if the server is not reachable
+        let initial = if context.live_updates {
+            context
+                .connect()
+                .map(|(stream, config_snapshot)| (Some(stream), config_snapshot))
+        } else {
+            context
+                .get_configuration_snapshot()
+                .map(|config_snapshot| (None, config_snapshot))
+        };
+        let (state, stream) = ClientState::start(&context, initial)?;
 
-        let sender = Self::update_configuration_on_change(
-            socket,
-            latest_config_snapshot,
-            access_token,
-            region.to_string(),
-            guid.to_string(),
-            collection_id.to_string(),
-            environment_id.to_string(),
-        );
+        // start monitoring configuration
+        let (terminator, receiver) = std::sync::mpsc::channel();
+        if context.live_updates {
+            let mut monitor = state.monitor(context);
+            thread::spawn(move || {
+                let result = Self::monitor_configuration(stream, &receiver, &mut monitor);
+                monitor.stopped(result);
+            });
+        }
 
-        Ok(sender)
+        Ok(AppConfigurationClientIBMCloud {
+            state,
+            _thread_terminator: terminator,
+        })
     }
-}
 
-impl AppConfigurationClient for AppConfigurationClientIBMCloud {
-    fn get_feature_ids(&self) -> Result<Vec<String>> {
-        Ok(self
-            .latest_config_snapshot
-            .lock()?
-            .features
-            .keys()
-            .cloned()
-            .collect())
+    /// Returns the state of the connection used to receive live-updates.
+    pub fn get_connection_state(&self) -> Result<ConnectionState> {
+        self.state.get_connection_state()
     }
 
-    fn get_feature(&self, feature_id: &str) -> Result<FeatureSnapshot> {
-        let config_snapshot = self.latest_config_snapshot.lock()?;
+    /// Returns the last time the configuration was successfully fetched from the server.
+    pub fn get_last_successful_fetch(&self) -> Result<Option<SystemTime>> {
+        self.state.get_last_successful_fetch()
+    }
 
-        // Get the feature from the snapshot
-        let feature = config_snapshot.get_feature(feature_id)?;
+    /// Returns the age of the configuration used to evaluate features and properties.
+    pub fn get_snapshot_age(&self) -> Result<Duration> {
+        self.state.get_snapshot_age()
+    }
 
-        // Get the segment rules that apply to this feature
-        let segments = {
-            let all_segment_ids = feature
-                .segment_rules
-                .iter()
-                .flat_map(|targeting_rule| {
-                    targeting_rule
-                        .rules
-                        .iter()
-                        .flat_map(|segment| &segment.segments)
-                })
-                .cloned()
-                .collect::<HashSet<String>>();
-            let segments: HashMap<String, Segment> = config_snapshot
-                .segments
-                .iter()
-                .filter(|&(key, _)| all_segment_ids.contains(key))
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect();
+    fn monitor_configuration<T: Transport>(
+        mut stream: Option<T::UpdateStream>,
+        terminator: &Receiver<()>,
+        monitor: &mut Monitor<T>,
+    ) -> Result<()> {
+        loop {
+            // If the sender has gone (AppConfiguration instance is dropped), then finish this thread
+            if let Err(TryRecvError::Disconnected) = terminator.try_recv() {
+                return Ok(());
+            }
 
-            // Integrity DB check: all segment_ids should be available in the snapshot
-            if all_segment_ids.len() != segments.len() {
-                return Err(ConfigurationAccessError::MissingSegments {
-                    resource_id: feature_id.to_string(),
+            monitor.tick()?;
+
+            let Some(current_stream) = stream.as_mut() else {
+                // Wait before reconnecting, but finish as soon as the client is dropped
+                let delay = monitor.next_reconnect_delay();
+                if let Err(RecvTimeoutError::Disconnected) = terminator.recv_timeout(delay) {
+                    return Ok(());
                 }
-                .into());
+                let reconnected = monitor
+                    .reconnect_span()
+                    .in_scope(|| monitor.context.connect());
+                stream = monitor.reconnected(reconnected)?;
+                continue;
+            };
+
+            let config_snapshot = current_stream
+                .wait_for_update()
+                .and_then(|_| monitor.context.get_configuration_snapshot());
+            if !monitor.updated(config_snapshot)? {
+                stream = None;
             }
+        }
+    }
+}
 
-            segments
-        };
+impl AppConfigurationClient for AppConfigurationClientIBMCloud {
+    fn get_feature_ids(&self) -> Result<Vec<String>> {
+        self.state.get_feature_ids()
+    }
 
-        Ok(FeatureSnapshot::new(feature.clone(), segments))
+    fn get_feature(&self, feature_id: &str) -> Result<FeatureSnapshot> {
+        self.state.get_feature(feature_id)
     }
 
     fn get_feature_proxy<'a>(&'a self, feature_id: &str) -> Result<FeatureProxy<'a>> {
@@ -271,58 +218,34 @@ impl AppConfigurationClient for AppConfigurationClientIBMCloud {
     }
 
     fn get_property_ids(&self) -> Result<Vec<String>> {
-        Ok(self
-            .latest_config_snapshot
-            .lock()
-            .map_err(|_| ConfigurationAccessError::LockAcquisitionError)?
-            .properties
-            .keys()
-            .cloned()
-            .collect())
+        self.state.get_property_ids()
     }
 
     fn get_property(&self, property_id: &str) -> Result<PropertySnapshot> {
-        let config_snapshot = self.latest_config_snapshot.lock()?;
-
-        // Get the property from the snapshot
-        let property = config_snapshot.get_property(property_id)?;
+        self.state.get_property(property_id)
+    }
 
-        // Get the segment rules that apply to this property
-        let segments = {
-            let all_segment_ids = property
-                .segment_rules
-                .iter()
-                .flat_map(|targeting_rule| {
-                    targeting_rule
-                        .rules
-                        .iter()
-                        .flat_map(|segment| &segment.segments)
-                })
-                .cloned()
-                .collect::<HashSet<String>>();
-            let segments: HashMap<String, Segment> = config_snapshot
-                .segments
-                .iter()
-                .filter(|&(key, _)| all_segment_ids.contains(key))
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect();
+    fn get_property_proxy(&self, property_id: &str) -> Result<PropertyProxy<'_>> {
+        Ok(PropertyProxy::new(self, property_id.to_string()))
+    }
 
-            // Integrity DB check: all segment_ids should be available in the snapshot
-            if all_segment_ids.len() != segments.len() {
-                // FIXME: Return some kind of DBIntegrity error
-                return Err(ConfigurationAccessError::MissingSegments {
-                    resource_id: property_id.to_string(),
-                }
-                .into());
-            }
+    fn evaluate_all(&self, entity: &impl Entity) -> Result<EvaluatedConfiguration> {
+        self.state.evaluate_all(entity)
+    }
 
-            segments
-        };
+    fn evaluate_bootstrap(&self, entity: &impl Entity) -> Result<EvaluatedBootstrap> {
+        self.state.evaluate_bootstrap(entity)
+    }
 
-        Ok(PropertySnapshot::new(property.clone(), segments))
+    fn subscribe(&self, filter: SubscriptionFilter) -> Result<Receiver<ConfigurationChange>> {
+        self.state.subscribe(filter)
     }
 
-    fn get_property_proxy(&self, property_id: &str) -> Result<PropertyProxy> {
-        Ok(PropertyProxy::new(self, property_id.to_string()))
+    fn subscribe_with_callback(
+        &self,
+        filter: SubscriptionFilter,
+        callback: ChangeCallback,
+    ) -> Result<()> {
+        self.state.subscribe_with_callback(filter, callback)
     }
 }
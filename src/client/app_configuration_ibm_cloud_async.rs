@@ -0,0 +1,261 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::client::context::ConnectionContext;
+use crate::client::evaluated_bootstrap::EvaluatedBootstrap;
+use crate::client::feature_proxy::FeatureProxy;
+use crate::client::feature_snapshot::FeatureSnapshot;
+use crate::client::http::HttpOptions;
+use crate::client::monitor::{ClientState, Monitor};
+use crate::client::options::ClientOptions;
+use crate::client::property_proxy::PropertyProxy;
+use crate::client::property_snapshot::PropertySnapshot;
+use crate::client::status::ConnectionState;
+use crate::client::subscriptions::{ChangeCallback, ConfigurationChange, SubscriptionFilter};
+use crate::client::transport::{AsyncTransport, AsyncUpdateStream, HttpTransport};
+use crate::errors::Result;
+use crate::evaluation::EvaluatedConfiguration;
+use crate::Entity;
+use std::sync::mpsc::Receiver;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::oneshot;
+use tracing::Instrument;
+
+use super::AppConfigurationClient;
+
+/// AppConfiguration client connection to IBM Cloud for applications running
+/// inside a `tokio` runtime.
+///
+/// The configuration is fetched using async requests and live-updates are received
+/// by a task spawned in the current runtime. Features and properties are read from
+/// an in-memory snapshot, so the [`AppConfigurationClient`] methods never block on
+/// network I/O and can be called from async code.
+#[derive(Debug)]
+pub struct AppConfigurationClientIBMCloudAsync {
+    pub(crate) state: ClientState,
+    pub(crate) _task_terminator: oneshot::Sender<()>,
+}
+
+impl AppConfigurationClientIBMCloudAsync {
+    /// Creates a new [`AppConfigurationClient`] connecting to IBM Cloud.
+    ///
+    /// This client keeps a websocket open to the server to receive live-updates
+    /// to features and properties. It must be called from within a `tokio` runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `apikey` - The encrypted API key.
+    /// * `region` - Region name where the App Configuration service instance is created
+    /// * `guid` - Instance ID of the App Configuration service. Obtain it from the service credentials section of the App Configuration dashboard
+    /// * `environment_id` - ID of the environment created in App Configuration service instance under the Environments section.
+    /// * `collection_id` - ID of the collection created in App Configuration service instance under the Collections section
+    pub async fn new(
+        apikey: &str,
+        region: &str,
+        guid: &str,
+        environment_id: &str,
+        collection_id: &str,
+    ) -> Result<Self> {
+        Self::new_with_options(
+            apikey,
+            region,
+            guid,
+            environment_id,
+            collection_id,
+            ClientOptions::default(),
+        )
+        .await
+    }
+
+    /// Creates a new [`AppConfigurationClient`] connecting to IBM Cloud using
+    /// the given [`ClientOptions`].
+    ///
+    /// See [`AppConfigurationClientIBMCloudAsync::new`] for a description of the arguments. Invalid
+    /// options are reported as a [`BuilderError`](crate::BuilderError).
+    pub async fn new_with_options(
+        apikey: &str,
+        region: &str,
+        guid: &str,
+        environment_id: &str,
+        collection_id: &str,
+        options: ClientOptions,
+    ) -> Result<Self> {
+        let transport = HttpTransport::new(HttpOptions::new(&options));
+        Self::new_with_transport(
+            apikey,
+            region,
+            guid,
+            environment_id,
+            collection_id,
+            options,
+            transport,
+        )
+        .await
+    }
+
+    /// Creates a new client that uses the given [`AsyncTransport`] to talk to the server.
+    ///
+    /// The HTTP settings in [`ClientOptions`] (timeouts and proxy) are not used, they
+    /// are up to the `transport`. See [`AppConfigurationClientIBMCloudAsync::new`] for a
+    /// description of the other arguments.
+    pub async fn new_with_transport<T: AsyncTransport>(
+        apikey: &str,
+        region: &str,
+        guid: &str,
+        environment_id: &str,
+        collection_id: &str,
+        options: ClientOptions,
+        transport: T,
+    ) -> Result<Self> {
+        options.validate()?;
+        let context = Arc::new(ConnectionContext::new(
+            apikey,
+            region,
+            guid,
+            environment_id,
+            collection_id,
+            options,
+            transport,
+        ));
+
+        // Populate initial configuration, using the local copies if the server is not reachable
+        let initial = if context.live_updates {
+            context
+                .connect_async()
+                .await
+                .map(|(stream, config_snapshot)| (Some(stream), config_snapshot))
+        } else {
+            context
+                .get_configuration_snapshot_async()
+                .await
+                .map(|config_snapshot| (None, config_snapshot))
+        };
+        let (state, stream) = ClientState::start(&context, initial)?;
+
+        // start monitoring configuration
+        let (sender, receiver) = oneshot::channel();
+        if context.live_updates {
+            let mut monitor = state.monitor(context);
+            tokio::spawn(async move {
+                let result = Self::monitor_configuration(stream, receiver, &mut monitor).await;
+                monitor.stopped(result);
+            });
+        }
+
+        Ok(Self {
+            state,
+            _task_terminator: sender,
+        })
+    }
+
+    /// Returns the state of the connection used to receive live-updates.
+    pub fn get_connection_state(&self) -> Result<ConnectionState> {
+        self.state.get_connection_state()
+    }
+
+    /// Returns the last time the configuration was successfully fetched from the server.
+    pub fn get_last_successful_fetch(&self) -> Result<Option<SystemTime>> {
+        self.state.get_last_successful_fetch()
+    }
+
+    /// Returns the age of the configuration used to evaluate features and properties.
+    pub fn get_snapshot_age(&self) -> Result<Duration> {
+        self.state.get_snapshot_age()
+    }
+
+    async fn monitor_configuration<T: AsyncTransport>(
+        mut stream: Option<T::AsyncUpdateStream>,
+        mut terminator: oneshot::Receiver<()>,
+        monitor: &mut Monitor<T>,
+    ) -> Result<()> {
+        loop {
+            monitor.tick()?;
+
+            let Some(current_stream) = stream.as_mut() else {
+                let delay = monitor.next_reconnect_delay();
+                let span = monitor.reconnect_span();
+                let reconnected = tokio::select! {
+                    // If the sender has gone (AppConfiguration instance is dropped), then finish this task
+                    _ = &mut terminator => return Ok(()),
+                    reconnected = async {
+                        tokio::time::sleep(delay).await;
+                        monitor.context.connect_async().instrument(span).await
+                    } => reconnected,
+                };
+                stream = monitor.reconnected(reconnected)?;
+                continue;
+            };
+
+            let config_snapshot = tokio::select! {
+                // If the sender has gone (AppConfiguration instance is dropped), then finish this task
+                _ = &mut terminator => return Ok(()),
+                config_snapshot = async {
+                    current_stream.wait_for_update().await?;
+                    monitor.context.get_configuration_snapshot_async().await
+                } => config_snapshot,
+            };
+            if !monitor.updated(config_snapshot)? {
+                stream = None;
+            }
+        }
+    }
+}
+
+impl AppConfigurationClient for AppConfigurationClientIBMCloudAsync {
+    fn get_feature_ids(&self) -> Result<Vec<String>> {
+        self.state.get_feature_ids()
+    }
+
+    fn get_feature(&self, feature_id: &str) -> Result<FeatureSnapshot> {
+        self.state.get_feature(feature_id)
+    }
+
+    fn get_feature_proxy<'a>(&'a self, feature_id: &str) -> Result<FeatureProxy<'a>> {
+        Ok(FeatureProxy::new(self, feature_id.to_string()))
+    }
+
+    fn get_property_ids(&self) -> Result<Vec<String>> {
+        self.state.get_property_ids()
+    }
+
+    fn get_property(&self, property_id: &str) -> Result<PropertySnapshot> {
+        self.state.get_property(property_id)
+    }
+
+    fn get_property_proxy(&self, property_id: &str) -> Result<PropertyProxy<'_>> {
+        Ok(PropertyProxy::new(self, property_id.to_string()))
+    }
+
+    fn evaluate_all(&self, entity: &impl Entity) -> Result<EvaluatedConfiguration> {
+        self.state.evaluate_all(entity)
+    }
+
+    fn evaluate_bootstrap(&self, entity: &impl Entity) -> Result<EvaluatedBootstrap> {
+        self.state.evaluate_bootstrap(entity)
+    }
+
+    fn subscribe(&self, filter: SubscriptionFilter) -> Result<Receiver<ConfigurationChange>> {
+        self.state.subscribe(filter)
+    }
+
+    fn subscribe_with_callback(
+        &self,
+        filter: SubscriptionFilter,
+        callback: ChangeCallback,
+    ) -> Result<()> {
+        self.state.subscribe_with_callback(filter, callback)
+    }
+}
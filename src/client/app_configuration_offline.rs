@@ -0,0 +1,125 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::client::cache::ConfigurationSnapshot;
+use crate::client::evaluated_bootstrap::EvaluatedBootstrap;
+use crate::client::feature_proxy::FeatureProxy;
+use crate::client::feature_snapshot::FeatureSnapshot;
+use crate::client::property_proxy::PropertyProxy;
+use crate::client::property_snapshot::PropertySnapshot;
+use crate::errors::{Error, Result};
+use crate::evaluation::EvaluatedConfiguration;
+use crate::hooks::Hooks;
+use crate::models::Configuration;
+use crate::{Entity, EvaluationHook};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::Arc;
+
+use super::AppConfigurationClient;
+
+/// AppConfiguration client using a configuration exported from IBM Cloud.
+///
+/// The configuration is loaded once (no network access is needed) and never
+/// changes, so subscribing to changes returns an error. Features and
+/// properties are evaluated exactly as in [`AppConfigurationClientIBMCloud`](crate::AppConfigurationClientIBMCloud).
+#[derive(Debug)]
+pub struct AppConfigurationOffline {
+    pub(crate) config_snapshot: ConfigurationSnapshot,
+    pub(crate) hooks: Hooks,
+}
+
+impl AppConfigurationOffline {
+    /// Creates a new [`AppConfigurationClient`] from the configuration stored in a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the JSON file exported from the App Configuration service.
+    /// * `environment_id` - ID of the environment (contained in the file) to use.
+    pub fn from_path<P: AsRef<Path>>(path: P, environment_id: &str) -> Result<Self> {
+        let file = File::open(path)?;
+        Self::from_reader(BufReader::new(file), environment_id)
+    }
+
+    /// Creates a new [`AppConfigurationClient`] reading the exported configuration from `reader`.
+    ///
+    /// See [`AppConfigurationOffline::from_path`] for a description of the arguments.
+    pub fn from_reader<R: Read>(reader: R, environment_id: &str) -> Result<Self> {
+        let configuration: Configuration =
+            serde_json::from_reader(reader).map_err(Error::ConfigurationParseError)?;
+        Self::from_configuration(configuration, environment_id)
+    }
+
+    /// Creates a new [`AppConfigurationClient`] from the exported configuration in `json`.
+    ///
+    /// See [`AppConfigurationOffline::from_path`] for a description of the arguments.
+    pub fn from_json_str(json: &str, environment_id: &str) -> Result<Self> {
+        let configuration: Configuration =
+            serde_json::from_str(json).map_err(Error::ConfigurationParseError)?;
+        Self::from_configuration(configuration, environment_id)
+    }
+
+    fn from_configuration(configuration: Configuration, environment_id: &str) -> Result<Self> {
+        Ok(Self {
+            config_snapshot: ConfigurationSnapshot::new(environment_id, configuration)?,
+            hooks: Hooks::default(),
+        })
+    }
+
+    /// Registers a hook called around the evaluation of features and properties.
+    /// See [`EvaluationHook`].
+    pub fn with_hook<H: EvaluationHook + 'static>(mut self, hook: H) -> Self {
+        self.hooks.push(Arc::new(hook));
+        self
+    }
+}
+
+impl AppConfigurationClient for AppConfigurationOffline {
+    fn get_feature_ids(&self) -> Result<Vec<String>> {
+        Ok(self.config_snapshot.features.keys().cloned().collect())
+    }
+
+    fn get_feature(&self, feature_id: &str) -> Result<FeatureSnapshot> {
+        self.config_snapshot
+            .get_feature_snapshot(feature_id)
+            .map(|feature| feature.with_hooks(&self.hooks))
+    }
+
+    fn get_feature_proxy<'a>(&'a self, feature_id: &str) -> Result<FeatureProxy<'a>> {
+        Ok(FeatureProxy::new(self, feature_id.to_string()))
+    }
+
+    fn get_property_ids(&self) -> Result<Vec<String>> {
+        Ok(self.config_snapshot.properties.keys().cloned().collect())
+    }
+
+    fn get_property(&self, property_id: &str) -> Result<PropertySnapshot> {
+        self.config_snapshot
+            .get_property_snapshot(property_id)
+            .map(|property| property.with_hooks(&self.hooks))
+    }
+
+    fn get_property_proxy(&self, property_id: &str) -> Result<PropertyProxy<'_>> {
+        Ok(PropertyProxy::new(self, property_id.to_string()))
+    }
+
+    fn evaluate_all(&self, entity: &impl Entity) -> Result<EvaluatedConfiguration> {
+        self.config_snapshot.evaluate_all(entity, None)
+    }
+
+    fn evaluate_bootstrap(&self, entity: &impl Entity) -> Result<EvaluatedBootstrap> {
+        self.config_snapshot.evaluate_bootstrap(entity, None)
+    }
+}
@@ -0,0 +1,486 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::client::endpoints::Endpoints;
+use crate::client::options::ClientOptions;
+use crate::client::reconnect::ReconnectPolicy;
+#[cfg(feature = "async")]
+use crate::client::transport::AsyncTransport;
+use crate::client::transport::Transport;
+use crate::client::AppConfigurationClientIBMCloud;
+#[cfg(feature = "async")]
+use crate::client::AppConfigurationClientIBMCloudAsync;
+use crate::errors::{BuilderError, Result};
+use crate::EvaluationHook;
+
+/// Builder to create clients connecting to IBM Cloud.
+///
+/// All the settings are validated when the client is built, returning a
+/// [`BuilderError`] describing the first invalid one.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use appconfiguration::{ClientBuilder, Result};
+/// # use std::time::Duration;
+/// # fn doctest_builder() -> Result<()> {
+/// let client = ClientBuilder::new()
+///     .apikey("api_key")
+///     .region("us-south")
+///     .guid("12345678-1234-1234-1234-12345678abcd")
+///     .environment_id("production")
+///     .collection_id("ecommerce")
+///     .timeout(Duration::from_secs(10))
+///     .cache_file("/var/cache/appconfiguration.json")
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ClientBuilder {
+    apikey: Option<String>,
+    region: Option<String>,
+    guid: Option<String>,
+    environment_id: Option<String>,
+    collection_id: Option<String>,
+    options: ClientOptions,
+}
+
+/// Settings already validated by the [`ClientBuilder`].
+struct ClientSettings {
+    apikey: String,
+    region: String,
+    guid: String,
+    environment_id: String,
+    collection_id: String,
+    options: ClientOptions,
+}
+
+impl ClientBuilder {
+    /// Creates a builder with the default [`ClientOptions`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the encrypted API key used to authenticate with IAM.
+    pub fn apikey(mut self, apikey: &str) -> Self {
+        self.apikey = Some(apikey.to_string());
+        self
+    }
+
+    /// Sets the region where the App Configuration service instance is created.
+    ///
+    /// Not required if a custom [`service_url`](ClientBuilder::service_url) is given.
+    pub fn region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    /// Sets the instance ID of the App Configuration service.
+    pub fn guid(mut self, guid: &str) -> Self {
+        self.guid = Some(guid.to_string());
+        self
+    }
+
+    /// Sets the ID of the environment to read features and properties from.
+    pub fn environment_id(mut self, environment_id: &str) -> Self {
+        self.environment_id = Some(environment_id.to_string());
+        self
+    }
+
+    /// Sets the ID of the collection to read features and properties from.
+    pub fn collection_id(mut self, collection_id: &str) -> Self {
+        self.collection_id = Some(collection_id.to_string());
+        self
+    }
+
+    /// Sets the timeout for each HTTP request sent to the server.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for the connect phase of each HTTP request.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.options.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Sets the URL of the proxy used for the HTTP requests, and to tunnel the websocket
+    /// receiving live-updates (it must be an `http` proxy unless live-updates are disabled).
+    pub fn proxy(mut self, proxy: &str) -> Self {
+        self.options.proxy = Some(proxy.to_string());
+        self
+    }
+
+    /// Sets all the endpoints the client connects to, replacing any URL given before.
+    pub fn endpoints(mut self, endpoints: Endpoints) -> Self {
+        self.options.endpoints = endpoints;
+        self
+    }
+
+    /// Uses the endpoints reachable through the IBM Cloud private network for the
+    /// URLs not given explicitly.
+    pub fn private_endpoint(mut self, private_endpoint: bool) -> Self {
+        self.options.endpoints.private_endpoint = private_endpoint;
+        self
+    }
+
+    /// Sets the root URL of the App Configuration service.
+    pub fn service_url(mut self, service_url: &str) -> Self {
+        self.options.endpoints.service_url = Some(service_url.to_string());
+        self
+    }
+
+    /// Sets the root URL used to open the websocket receiving live-updates.
+    pub fn websocket_url(mut self, websocket_url: &str) -> Self {
+        self.options.endpoints.websocket_url = Some(websocket_url.to_string());
+        self
+    }
+
+    /// Sets the root URL of the IAM service issuing the access tokens.
+    pub fn iam_url(mut self, iam_url: &str) -> Self {
+        self.options.endpoints.iam_url = Some(iam_url.to_string());
+        self
+    }
+
+    /// Sets the file where every configuration fetched from the server is stored.
+    /// See [`ClientOptions::cache_file`].
+    pub fn cache_file<P: Into<PathBuf>>(mut self, cache_file: P) -> Self {
+        self.options.cache_file = Some(cache_file.into());
+        self
+    }
+
+    /// Sets the exported configuration to use if the server cannot be reached at startup.
+    /// See [`ClientOptions::bootstrap_file`].
+    pub fn bootstrap_file<P: Into<PathBuf>>(mut self, bootstrap_file: P) -> Self {
+        self.options.bootstrap_file = Some(bootstrap_file.into());
+        self
+    }
+
+    /// Sets how to reconnect when the connection to receive live-updates is lost.
+    pub fn reconnect_policy(mut self, reconnect_policy: ReconnectPolicy) -> Self {
+        self.options.reconnect_policy = reconnect_policy;
+        self
+    }
+
+    /// Enables or disables live-updates (enabled by default).
+    pub fn live_updates(mut self, live_updates: bool) -> Self {
+        self.options.live_updates = live_updates;
+        self
+    }
+
+    /// Enables or disables the `tracing` warnings about connection problems (enabled by default).
+    pub fn logging(mut self, logging: bool) -> Self {
+        self.options.logging = logging;
+        self
+    }
+
+    /// Enables or disables sending the usage of features and properties to the server
+    /// (disabled by default). See [`ClientOptions::usage_metering`].
+    pub fn usage_metering(mut self, usage_metering: bool) -> Self {
+        self.options.usage_metering = usage_metering;
+        self
+    }
+
+    /// Sets how often the usage is sent to the server (every 10 minutes by default).
+    pub fn metering_interval(mut self, metering_interval: Duration) -> Self {
+        self.options.metering_interval = metering_interval;
+        self
+    }
+
+    /// Registers a hook called around the evaluation of features and properties.
+    /// See [`EvaluationHook`].
+    pub fn hook<H: EvaluationHook + 'static>(mut self, hook: H) -> Self {
+        self.options.hooks.push(Arc::new(hook));
+        self
+    }
+
+    /// Validates the settings and creates an [`AppConfigurationClientIBMCloud`].
+    pub fn build(self) -> Result<AppConfigurationClientIBMCloud> {
+        let settings = self.validate()?;
+        AppConfigurationClientIBMCloud::new_with_options(
+            &settings.apikey,
+            &settings.region,
+            &settings.guid,
+            &settings.environment_id,
+            &settings.collection_id,
+            settings.options,
+        )
+    }
+
+    /// Validates the settings and creates an [`AppConfigurationClientIBMCloud`] that
+    /// uses the given [`Transport`] to talk to the server.
+    ///
+    /// See [`AppConfigurationClientIBMCloud::new_with_transport`].
+    pub fn build_with_transport<T: Transport>(
+        self,
+        transport: T,
+    ) -> Result<AppConfigurationClientIBMCloud> {
+        let settings = self.validate()?;
+        AppConfigurationClientIBMCloud::new_with_transport(
+            &settings.apikey,
+            &settings.region,
+            &settings.guid,
+            &settings.environment_id,
+            &settings.collection_id,
+            settings.options,
+            transport,
+        )
+    }
+
+    /// Validates the settings and creates an [`AppConfigurationClientIBMCloudAsync`].
+    #[cfg(feature = "async")]
+    pub async fn build_async(self) -> Result<AppConfigurationClientIBMCloudAsync> {
+        let settings = self.validate()?;
+        AppConfigurationClientIBMCloudAsync::new_with_options(
+            &settings.apikey,
+            &settings.region,
+            &settings.guid,
+            &settings.environment_id,
+            &settings.collection_id,
+            settings.options,
+        )
+        .await
+    }
+
+    /// Validates the settings and creates an [`AppConfigurationClientIBMCloudAsync`] that
+    /// uses the given [`AsyncTransport`] to talk to the server.
+    ///
+    /// See [`AppConfigurationClientIBMCloudAsync::new_with_transport`].
+    #[cfg(feature = "async")]
+    pub async fn build_async_with_transport<T: AsyncTransport>(
+        self,
+        transport: T,
+    ) -> Result<AppConfigurationClientIBMCloudAsync> {
+        let settings = self.validate()?;
+        AppConfigurationClientIBMCloudAsync::new_with_transport(
+            &settings.apikey,
+            &settings.region,
+            &settings.guid,
+            &settings.environment_id,
+            &settings.collection_id,
+            settings.options,
+            transport,
+        )
+        .await
+    }
+
+    fn validate(self) -> std::result::Result<ClientSettings, BuilderError> {
+        let apikey = required("apikey", self.apikey)?;
+        // The region is only used to compose the default service URL
+        let region = match self.options.endpoints.service_url {
+            Some(_) => self.region.unwrap_or_default(),
+            None => required("region", self.region)?,
+        };
+        let guid = required("guid", self.guid)?;
+        let environment_id = required("environment_id", self.environment_id)?;
+        let collection_id = required("collection_id", self.collection_id)?;
+
+        self.options.validate()?;
+
+        Ok(ClientSettings {
+            apikey,
+            region,
+            guid,
+            environment_id,
+            collection_id,
+            options: self.options,
+        })
+    }
+}
+
+fn required(
+    setting: &'static str,
+    value: Option<String>,
+) -> std::result::Result<String, BuilderError> {
+    match value {
+        None => Err(BuilderError::MissingSetting(setting)),
+        Some(value) if value.trim().is_empty() => Err(BuilderError::EmptySetting(setting)),
+        Some(value) => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+            .apikey("apikey")
+            .region("us-south")
+            .guid("guid")
+            .environment_id("environment_id")
+            .collection_id("collection_id")
+    }
+
+    fn validate(builder: ClientBuilder) -> std::result::Result<ClientSettings, BuilderError> {
+        builder.validate()
+    }
+
+    #[test]
+    fn test_valid_settings() {
+        let settings = validate(
+            builder()
+                .timeout(Duration::from_secs(5))
+                .live_updates(false)
+                .logging(false),
+        )
+        .unwrap();
+        assert_eq!(settings.apikey, "apikey");
+        assert_eq!(settings.region, "us-south");
+        assert_eq!(settings.guid, "guid");
+        assert_eq!(settings.environment_id, "environment_id");
+        assert_eq!(settings.collection_id, "collection_id");
+        assert_eq!(settings.options.timeout, Some(Duration::from_secs(5)));
+        assert!(!settings.options.live_updates);
+        assert!(!settings.options.logging);
+    }
+
+    #[test]
+    fn test_missing_and_empty_settings() {
+        let result = validate(ClientBuilder::new().apikey("apikey"));
+        assert_eq!(result.err(), Some(BuilderError::MissingSetting("region")));
+
+        let result = validate(builder().environment_id(" "));
+        assert_eq!(
+            result.err(),
+            Some(BuilderError::EmptySetting("environment_id"))
+        );
+
+        // The region is not needed with a custom service URL
+        let builder = ClientBuilder::new()
+            .apikey("apikey")
+            .guid("guid")
+            .environment_id("environment_id")
+            .collection_id("collection_id")
+            .service_url("http://localhost:8080");
+        assert!(validate(builder).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_urls() {
+        let result = validate(builder().service_url("wss://example.com"));
+        assert!(matches!(
+            result.err(),
+            Some(BuilderError::InvalidUrl {
+                setting: "service_url",
+                ..
+            })
+        ));
+
+        let result = validate(builder().websocket_url("not a url"));
+        assert!(matches!(
+            result.err(),
+            Some(BuilderError::InvalidUrl {
+                setting: "websocket_url",
+                ..
+            })
+        ));
+
+        let result = validate(builder().proxy("http://proxy.example.com:3128"));
+        assert!(result.is_ok());
+
+        // The websocket can only be tunneled through http proxies
+        let result = validate(builder().proxy("https://proxy.example.com:3128"));
+        assert!(matches!(
+            result.err(),
+            Some(BuilderError::InvalidUrl {
+                setting: "proxy",
+                ..
+            })
+        ));
+        let result = validate(
+            builder()
+                .proxy("https://proxy.example.com:3128")
+                .live_updates(false),
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_invalid_durations_and_paths() {
+        let result = validate(builder().connect_timeout(Duration::ZERO));
+        assert_eq!(
+            result.err(),
+            Some(BuilderError::ZeroDuration("connect_timeout"))
+        );
+
+        let result = validate(builder().metering_interval(Duration::ZERO));
+        assert_eq!(
+            result.err(),
+            Some(BuilderError::ZeroDuration("metering_interval"))
+        );
+
+        let result = validate(builder().cache_file("/does/not/exist/cache.json"));
+        assert!(matches!(
+            result.err(),
+            Some(BuilderError::InvalidPath {
+                setting: "cache_file",
+                ..
+            })
+        ));
+
+        let result = validate(builder().bootstrap_file("/does/not/exist/bootstrap.json"));
+        assert!(matches!(
+            result.err(),
+            Some(BuilderError::InvalidPath {
+                setting: "bootstrap_file",
+                ..
+            })
+        ));
+
+        assert!(validate(builder().cache_file("cache.json")).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_reconnect_policy() {
+        let invalid_setting = |policy| match validate(builder().reconnect_policy(policy)) {
+            Err(BuilderError::InvalidSetting { setting, .. }) => setting,
+            other => panic!("Unexpected result {:?}", other.map(|_| ())),
+        };
+
+        for multiplier in [f64::NAN, f64::INFINITY, -2.0, 0.5] {
+            let policy = ReconnectPolicy {
+                multiplier,
+                ..Default::default()
+            };
+            assert_eq!(invalid_setting(policy), "reconnect_policy.multiplier");
+        }
+        for jitter in [f64::NAN, -0.1, 1.5] {
+            let policy = ReconnectPolicy {
+                jitter,
+                ..Default::default()
+            };
+            assert_eq!(invalid_setting(policy), "reconnect_policy.jitter");
+        }
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(5),
+            ..Default::default()
+        };
+        assert_eq!(invalid_setting(policy), "reconnect_policy.initial_delay");
+
+        let policy = ReconnectPolicy {
+            multiplier: 1.0,
+            jitter: 1.0,
+            ..Default::default()
+        };
+        assert!(validate(builder().reconnect_policy(policy)).is_ok());
+    }
+}
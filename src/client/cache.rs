@@ -12,16 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+use std::time::{Duration, SystemTime};
 
-use crate::errors::{ConfigurationAccessError, Result};
-use crate::models::{Configuration, Feature, Property, Segment};
+use crate::client::evaluated_bootstrap::{EvaluatedBootstrap, EvaluatedFeature, EvaluatedProperty};
+use crate::client::feature_snapshot::{FeatureEvaluation, FeatureSnapshot};
+use crate::client::metering::Metering;
+use crate::client::metrics;
+use crate::client::property_snapshot::{PropertyEvaluation, PropertySnapshot};
+use crate::entity::Entity;
+use crate::errors::{ConfigurationAccessError, Error, Result};
+use crate::evaluation::EvaluatedConfiguration;
+use crate::models::{
+    Configuration, Environment, Feature, Property, Segment, SegmentRule, TargetingRule,
+};
+use murmur3::murmur3_32;
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub(crate) struct ConfigurationSnapshot {
     pub(crate) features: HashMap<String, Feature>,
     pub(crate) properties: HashMap<String, Property>,
     pub(crate) segments: HashMap<String, Segment>,
+    pub(crate) created_at: SystemTime,
+    /// Identifies the content of the snapshot: same configuration, same version.
+    pub(crate) version: String,
+    pub(crate) environment_id: String,
 }
 
 impl ConfigurationSnapshot {
@@ -43,6 +59,167 @@ impl ConfigurationSnapshot {
         })
     }
 
+    /// Returns the time elapsed since this snapshot was created.
+    pub fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.created_at)
+            .unwrap_or_default()
+    }
+
+    /// Records the age of this snapshot in the `appconfiguration_snapshot_age_seconds` gauge.
+    pub fn record_age(&self) {
+        metrics::record_snapshot_age(&self.environment_id, self.age());
+    }
+
+    /// Returns a [`FeatureSnapshot`] with the feature and all the segments it references.
+    pub fn get_feature_snapshot(&self, feature_id: &str) -> Result<FeatureSnapshot> {
+        self.record_age();
+        let feature = self.get_feature(feature_id)?;
+        let segments = self.get_segments_for(feature_id, &feature.segment_rules)?;
+        Ok(FeatureSnapshot::new(feature.clone(), segments))
+    }
+
+    /// Returns a [`PropertySnapshot`] with the property and all the segments it references.
+    pub fn get_property_snapshot(&self, property_id: &str) -> Result<PropertySnapshot> {
+        self.record_age();
+        let property = self.get_property(property_id)?;
+        let segments = self.get_segments_for(property_id, &property.segment_rules)?;
+        Ok(PropertySnapshot::new(property.clone(), segments))
+    }
+
+    /// Evaluates all the features and properties for the given entity, and returns them
+    /// without any targeting information. The ones that fail to evaluate are left out.
+    pub fn evaluate_bootstrap(
+        &self,
+        entity: &impl Entity,
+        metering: Option<&Metering>,
+    ) -> Result<EvaluatedBootstrap> {
+        let evaluated = self.evaluate_all(entity, metering)?;
+        for (id, e) in evaluated.feature_errors.iter() {
+            tracing::warn!(
+                feature_id = %id,
+                error = %e,
+                "Feature left out of the bootstrap, it failed to evaluate"
+            );
+        }
+        for (id, e) in evaluated.property_errors.iter() {
+            tracing::warn!(
+                property_id = %id,
+                error = %e,
+                "Property left out of the bootstrap, it failed to evaluate"
+            );
+        }
+        let features = evaluated
+            .features
+            .into_iter()
+            .map(|(id, details)| {
+                let feature = &self.features[&id];
+                let evaluated_feature = EvaluatedFeature {
+                    name: feature.name.clone(),
+                    enabled: feature.enabled,
+                    value: details.value,
+                };
+                (id, evaluated_feature)
+            })
+            .collect();
+        let properties = evaluated
+            .properties
+            .into_iter()
+            .map(|(id, details)| {
+                let property = &self.properties[&id];
+                let evaluated_property = EvaluatedProperty {
+                    name: property.name.clone(),
+                    value: details.value,
+                };
+                (id, evaluated_property)
+            })
+            .collect();
+        Ok(EvaluatedBootstrap {
+            version: self.version.clone(),
+            entity_id: entity.get_id(),
+            features,
+            properties,
+        })
+    }
+
+    /// Evaluates all the features and properties for the given entity.
+    ///
+    /// A feature or property that fails to evaluate doesn't prevent evaluating the
+    /// others: its error is reported in `feature_errors` or `property_errors`. The successful
+    /// evaluations are recorded in `metering`, as when evaluating them one by one.
+    pub fn evaluate_all(
+        &self,
+        entity: &impl Entity,
+        metering: Option<&Metering>,
+    ) -> Result<EvaluatedConfiguration> {
+        self.record_age();
+        let mut evaluated = EvaluatedConfiguration::default();
+        for (id, feature) in self.features.iter() {
+            match FeatureEvaluation::new(feature, &self.segments).evaluate_details(entity) {
+                Ok(details) => {
+                    if let Some(metering) = metering {
+                        metering.record_feature(id, entity.get_id(), details.segment_id.as_deref());
+                    }
+                    evaluated.features.insert(id.clone(), details);
+                }
+                Err(e) => {
+                    evaluated.feature_errors.insert(id.clone(), e);
+                }
+            }
+        }
+        for (id, property) in self.properties.iter() {
+            match PropertyEvaluation::new(property, &self.segments).evaluate_details(entity) {
+                Ok(details) => {
+                    if let Some(metering) = metering {
+                        metering.record_property(
+                            id,
+                            entity.get_id(),
+                            details.segment_id.as_deref(),
+                        );
+                    }
+                    evaluated.properties.insert(id.clone(), details);
+                }
+                Err(e) => {
+                    evaluated.property_errors.insert(id.clone(), e);
+                }
+            }
+        }
+        Ok(evaluated)
+    }
+
+    fn get_segments_for(
+        &self,
+        resource_id: &str,
+        segment_rules: &[TargetingRule],
+    ) -> Result<HashMap<String, Segment>> {
+        let all_segment_ids = segment_rules
+            .iter()
+            .flat_map(|targeting_rule| {
+                targeting_rule
+                    .rules
+                    .iter()
+                    .flat_map(|segment| &segment.segments)
+            })
+            .cloned()
+            .collect::<HashSet<String>>();
+        let segments: HashMap<String, Segment> = self
+            .segments
+            .iter()
+            .filter(|&(key, _)| all_segment_ids.contains(key))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        // Integrity DB check: all segment_ids should be available in the snapshot
+        if all_segment_ids.len() != segments.len() {
+            return Err(ConfigurationAccessError::MissingSegments {
+                resource_id: resource_id.to_string(),
+            }
+            .into());
+        }
+
+        Ok(segments)
+    }
+
     pub fn new(environment_id: &str, configuration: Configuration) -> Result<Self> {
         let environment = configuration
             .environments
@@ -52,6 +229,7 @@ impl ConfigurationSnapshot {
                 environment_id: environment_id.to_string(),
             })?;
         // FIXME: why not filtering for collection here?
+        let version = Self::compute_version(&environment, &configuration.segments)?;
 
         let mut features = HashMap::new();
         for feature in environment.features {
@@ -64,21 +242,31 @@ impl ConfigurationSnapshot {
         }
 
         let mut segments = HashMap::new();
-        for segment in configuration.segments {
-            segments.insert(segment.segment_id.clone(), segment.clone());
+        for mut segment in configuration.segments {
+            segment.rules.iter_mut().for_each(SegmentRule::precompile);
+            segments.insert(segment.segment_id.clone(), segment);
         }
         Ok(ConfigurationSnapshot {
             features,
             properties,
             segments,
+            created_at: SystemTime::now(),
+            version,
+            environment_id: environment_id.to_string(),
         })
     }
+
+    fn compute_version(environment: &Environment, segments: &[Segment]) -> Result<String> {
+        let data =
+            serde_json::to_vec(&(environment, segments)).map_err(Error::ConfigurationParseError)?;
+        let hash = murmur3_32(&mut Cursor::new(data), 0)?;
+        Ok(format!("{hash:08x}"))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::errors::Error;
     use crate::models::tests::example_configuration_enterprise;
     use crate::models::Configuration;
     use rstest::*;
@@ -94,4 +282,32 @@ mod tests {
                 Error::ConfigurationAccessError(ref e)
                 if matches!(e, ConfigurationAccessError::EnvironmentNotFound { ref environment_id} if environment_id == "does_for_sure_not_exist")));
     }
+
+    #[rstest]
+    fn test_segment_rules_are_precompiled(mut example_configuration_enterprise: Configuration) {
+        let segment = &mut example_configuration_enterprise.segments[0];
+        segment.rules[0].operator = "in".into();
+        let segment_id = segment.segment_id.clone();
+
+        let snapshot = ConfigurationSnapshot::new("dev", example_configuration_enterprise).unwrap();
+
+        let rules = &snapshot.segments[&segment_id].rules;
+        assert!(rules[0].compiled_values.is_some());
+        assert!(rules[1..].iter().all(|rule| rule.compiled_values.is_none()));
+    }
+
+    #[rstest]
+    fn test_version(example_configuration_enterprise: Configuration) {
+        let json = serde_json::to_string(&example_configuration_enterprise).unwrap();
+        let snapshot = ConfigurationSnapshot::new("dev", example_configuration_enterprise).unwrap();
+
+        let same_configuration: Configuration = serde_json::from_str(&json).unwrap();
+        let same_snapshot = ConfigurationSnapshot::new("dev", same_configuration).unwrap();
+        assert_eq!(snapshot.version, same_snapshot.version);
+
+        let mut other_configuration: Configuration = serde_json::from_str(&json).unwrap();
+        other_configuration.environments[0].features[0].enabled ^= true;
+        let other_snapshot = ConfigurationSnapshot::new("dev", other_configuration).unwrap();
+        assert_ne!(snapshot.version, other_snapshot.version);
+    }
 }
@@ -0,0 +1,197 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "async")]
+use tracing::Instrument;
+use tracing::Span;
+
+use crate::client::cache::ConfigurationSnapshot;
+use crate::client::http;
+use crate::client::metering::UsageReport;
+use crate::client::metrics;
+use crate::client::options::ClientOptions;
+use crate::client::persistence::Persistence;
+use crate::client::reconnect::ReconnectPolicy;
+use crate::client::token::TokenManager;
+#[cfg(feature = "async")]
+use crate::client::transport::AsyncTransport;
+use crate::client::transport::{ServiceInstance, Transport};
+use crate::errors::{Error, Result};
+use crate::hooks::Hooks;
+
+/// Everything a client connecting to IBM Cloud (and the thread or task monitoring
+/// the configuration) needs to fetch the configuration from the server.
+#[derive(Debug)]
+pub(crate) struct ConnectionContext<T> {
+    pub(crate) instance: ServiceInstance,
+    pub(crate) transport: T,
+    pub(crate) token_manager: TokenManager,
+    pub(crate) persistence: Persistence,
+    pub(crate) reconnect_policy: ReconnectPolicy,
+    pub(crate) live_updates: bool,
+    pub(crate) logging: bool,
+    /// Parent of the spans and events of the client, identifying the instance,
+    /// environment and collection.
+    pub(crate) span: Span,
+    pub(crate) usage_metering: bool,
+    pub(crate) metering_interval: Duration,
+    pub(crate) hooks: Hooks,
+}
+
+impl<T> ConnectionContext<T> {
+    pub(crate) fn new(
+        apikey: &str,
+        region: &str,
+        guid: &str,
+        environment_id: &str,
+        collection_id: &str,
+        options: ClientOptions,
+        transport: T,
+    ) -> Self {
+        Self {
+            instance: ServiceInstance::new(
+                region,
+                guid,
+                environment_id,
+                collection_id,
+                options.endpoints,
+            ),
+            transport,
+            token_manager: TokenManager::new(apikey),
+            persistence: Persistence::new(options.cache_file, options.bootstrap_file),
+            reconnect_policy: options.reconnect_policy,
+            live_updates: options.live_updates,
+            logging: options.logging,
+            span: tracing::info_span!("appconfiguration", guid, environment_id, collection_id),
+            usage_metering: options.usage_metering,
+            metering_interval: options.metering_interval,
+            hooks: Hooks::new(options.hooks),
+        }
+    }
+
+    /// Returns the configuration stored in the local cache or bootstrap file.
+    pub(crate) fn load_local_copy(&self) -> Option<ConfigurationSnapshot> {
+        self.persistence
+            .load(self.instance.environment_id(), |path, e| {
+                if self.logging {
+                    tracing::warn!(
+                        parent: &self.span,
+                        path = %path.display(),
+                        error = %e,
+                        "Cannot use stored configuration"
+                    );
+                }
+            })
+    }
+
+    /// Parses the configuration received from the server and stores it in the
+    /// local cache (if any).
+    fn parse_configuration(&self, body: &str) -> Result<ConfigurationSnapshot> {
+        let configuration = http::parse_configuration(body)?;
+        if let Err(e) = self.persistence.save(&configuration) {
+            if self.logging {
+                tracing::warn!(
+                    parent: &self.span,
+                    error = %e,
+                    "Cannot store configuration in the local cache"
+                );
+            }
+        }
+        ConfigurationSnapshot::new(self.instance.environment_id(), configuration)
+    }
+}
+
+impl<T: Transport> ConnectionContext<T> {
+    pub(crate) fn get_access_token(&self) -> Result<String> {
+        self.token_manager.get_access_token(|apikey| {
+            http::parse_access_token(self.transport.fetch_token(&self.instance, apikey)?)
+        })
+    }
+
+    pub(crate) fn get_configuration_snapshot(&self) -> Result<ConfigurationSnapshot> {
+        let _span = tracing::info_span!(parent: &self.span, "fetch_configuration").entered();
+        let started = Instant::now();
+        let result = self
+            .get_access_token()
+            .and_then(|token| self.transport.fetch_configuration(&self.instance, &token))
+            .and_then(|body| self.parse_configuration(&body));
+        metrics::record_configuration_fetch(&self.instance, started, result.is_ok());
+        result
+    }
+
+    pub(crate) fn send_usage(&self, report: &UsageReport) -> Result<()> {
+        let body = serde_json::to_string(report).map_err(|e| Error::Other(e.to_string()))?;
+        self.transport
+            .send_usage(&self.instance, &self.get_access_token()?, &body)
+    }
+
+    pub(crate) fn open_update_stream(&self) -> Result<T::UpdateStream> {
+        self.transport
+            .open_update_stream(&self.instance, &self.get_access_token()?)
+    }
+
+    /// Opens a new update stream and fetches the whole configuration again, so no
+    /// update is missed while the client was disconnected.
+    pub(crate) fn connect(&self) -> Result<(T::UpdateStream, ConfigurationSnapshot)> {
+        let stream = self.open_update_stream()?;
+        let config_snapshot = self.get_configuration_snapshot()?;
+        Ok((stream, config_snapshot))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncTransport> ConnectionContext<T> {
+    pub(crate) async fn get_access_token_async(&self) -> Result<String> {
+        self.token_manager
+            .get_access_token_async(|apikey| async move {
+                http::parse_access_token(
+                    self.transport
+                        .fetch_token_async(&self.instance, &apikey)
+                        .await?,
+                )
+            })
+            .await
+    }
+
+    pub(crate) async fn get_configuration_snapshot_async(&self) -> Result<ConfigurationSnapshot> {
+        let started = Instant::now();
+        let result = async {
+            let token = self.get_access_token_async().await?;
+            let body = self
+                .transport
+                .fetch_configuration_async(&self.instance, &token)
+                .await?;
+            self.parse_configuration(&body)
+        }
+        .instrument(tracing::info_span!(parent: &self.span, "fetch_configuration"))
+        .await;
+        metrics::record_configuration_fetch(&self.instance, started, result.is_ok());
+        result
+    }
+
+    /// Async version of [`ConnectionContext::connect`].
+    pub(crate) async fn connect_async(
+        &self,
+    ) -> Result<(T::AsyncUpdateStream, ConfigurationSnapshot)> {
+        let token = self.get_access_token_async().await?;
+        let stream = self
+            .transport
+            .open_update_stream_async(&self.instance, &token)
+            .await?;
+        let config_snapshot = self.get_configuration_snapshot_async().await?;
+        Ok((stream, config_snapshot))
+    }
+}
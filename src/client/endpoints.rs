@@ -0,0 +1,249 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// URLs of the services used by the clients connecting to IBM Cloud.
+///
+/// Every endpoint is optional, the public IBM Cloud ones are used by default.
+///
+/// # Examples
+///
+/// ```
+/// # use appconfiguration::Endpoints;
+/// // Use the private network
+/// let private = Endpoints::private();
+///
+/// // Use a local server implementing both the App Configuration and IAM APIs
+/// let local = Endpoints::local_server("http://localhost:8080");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Endpoints {
+    /// Root URL of the App Configuration service, e.g. `https://us-south.apprapp.cloud.ibm.com`.
+    /// Defaults to the public (or private) endpoint for the client region.
+    pub service_url: Option<String>,
+
+    /// Root URL used to open the websocket receiving live-updates. Defaults to the
+    /// `service_url` with the `wss` (or `ws`) scheme.
+    pub websocket_url: Option<String>,
+
+    /// Root URL of the IAM service issuing the access tokens. Defaults to [`Endpoints::IAM_URL`]
+    /// (or [`Endpoints::PRIVATE_IAM_URL`]).
+    pub iam_url: Option<String>,
+
+    /// Use the endpoints reachable only through the IBM Cloud private network
+    /// (`https://private.{region}.apprapp.cloud.ibm.com`) for the URLs not given.
+    pub private_endpoint: bool,
+}
+
+impl Endpoints {
+    /// IAM service in IBM Cloud.
+    pub const IAM_URL: &str = "https://iam.cloud.ibm.com";
+
+    /// IAM service in IBM Cloud, reachable only through the IBM Cloud private network.
+    pub const PRIVATE_IAM_URL: &str = "https://private.iam.cloud.ibm.com";
+
+    /// IAM service in the IBM Cloud staging environment.
+    pub const STAGING_IAM_URL: &str = "https://iam.test.cloud.ibm.com";
+
+    /// Returns the endpoints reachable through the IBM Cloud private network.
+    pub fn private() -> Self {
+        Self {
+            private_endpoint: true,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the public endpoints, authenticating with the IAM service in the
+    /// IBM Cloud staging environment.
+    pub fn staging() -> Self {
+        Self {
+            iam_url: Some(Self::STAGING_IAM_URL.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the endpoints to use a server at `url` that serves both the App
+    /// Configuration and the IAM APIs, for example a stand-in server for tests.
+    pub fn local_server(url: &str) -> Self {
+        Self {
+            service_url: Some(url.to_string()),
+            iam_url: Some(url.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn get_service_url(&self, region: &str) -> String {
+        match &self.service_url {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None if self.private_endpoint => {
+                format!("https://private.{region}.apprapp.cloud.ibm.com")
+            }
+            None => format!("https://{region}.apprapp.cloud.ibm.com"),
+        }
+    }
+
+    pub(crate) fn get_configuration_url(&self, region: &str, guid: &str) -> String {
+        format!(
+            "{}/apprapp/feature/v1/instances/{guid}/config",
+            self.get_service_url(region)
+        )
+    }
+
+    pub(crate) fn get_usage_url(&self, region: &str, guid: &str) -> String {
+        format!(
+            "{}/apprapp/events/v1/instances/{guid}/usage",
+            self.get_service_url(region)
+        )
+    }
+
+    pub(crate) fn get_websocket_url(&self, region: &str) -> String {
+        let root = match &self.websocket_url {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None => {
+                let service_url = self.get_service_url(region);
+                if let Some(host) = service_url.strip_prefix("https://") {
+                    format!("wss://{host}")
+                } else if let Some(host) = service_url.strip_prefix("http://") {
+                    format!("ws://{host}")
+                } else {
+                    service_url
+                }
+            }
+        };
+        format!("{root}/apprapp/wsfeature")
+    }
+
+    pub(crate) fn get_token_url(&self) -> String {
+        let default_url = if self.private_endpoint {
+            Self::PRIVATE_IAM_URL
+        } else {
+            Self::IAM_URL
+        };
+        let root = self
+            .iam_url
+            .as_deref()
+            .unwrap_or(default_url)
+            .trim_end_matches('/');
+        format!("{root}/identity/token")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_endpoints() {
+        let endpoints = Endpoints::default();
+        assert_eq!(
+            endpoints.get_configuration_url("us-south", "guid"),
+            "https://us-south.apprapp.cloud.ibm.com/apprapp/feature/v1/instances/guid/config"
+        );
+        assert_eq!(
+            endpoints.get_usage_url("us-south", "guid"),
+            "https://us-south.apprapp.cloud.ibm.com/apprapp/events/v1/instances/guid/usage"
+        );
+        assert_eq!(
+            endpoints.get_websocket_url("us-south"),
+            "wss://us-south.apprapp.cloud.ibm.com/apprapp/wsfeature"
+        );
+        assert_eq!(
+            endpoints.get_token_url(),
+            "https://iam.cloud.ibm.com/identity/token"
+        );
+    }
+
+    #[test]
+    fn test_custom_endpoints() {
+        let endpoints = Endpoints {
+            service_url: Some("http://localhost:8080/".to_string()),
+            websocket_url: None,
+            iam_url: Some("http://localhost:8081".to_string()),
+            private_endpoint: false,
+        };
+        assert_eq!(
+            endpoints.get_configuration_url("us-south", "guid"),
+            "http://localhost:8080/apprapp/feature/v1/instances/guid/config"
+        );
+        assert_eq!(
+            endpoints.get_usage_url("us-south", "guid"),
+            "http://localhost:8080/apprapp/events/v1/instances/guid/usage"
+        );
+        assert_eq!(
+            endpoints.get_websocket_url("us-south"),
+            "ws://localhost:8080/apprapp/wsfeature"
+        );
+        assert_eq!(
+            endpoints.get_token_url(),
+            "http://localhost:8081/identity/token"
+        );
+
+        let endpoints = Endpoints {
+            websocket_url: Some("wss://updates.example.com".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            endpoints.get_websocket_url("eu-de"),
+            "wss://updates.example.com/apprapp/wsfeature"
+        );
+    }
+
+    #[test]
+    fn test_private_endpoints() {
+        let endpoints = Endpoints::private();
+        assert_eq!(
+            endpoints.get_configuration_url("us-south", "guid"),
+            "https://private.us-south.apprapp.cloud.ibm.com/apprapp/feature/v1/instances/guid/config"
+        );
+        assert_eq!(
+            endpoints.get_websocket_url("us-south"),
+            "wss://private.us-south.apprapp.cloud.ibm.com/apprapp/wsfeature"
+        );
+        assert_eq!(
+            endpoints.get_token_url(),
+            "https://private.iam.cloud.ibm.com/identity/token"
+        );
+
+        // Explicit URLs take precedence
+        let endpoints = Endpoints {
+            iam_url: Some(Endpoints::STAGING_IAM_URL.to_string()),
+            ..Endpoints::private()
+        };
+        assert_eq!(
+            endpoints.get_token_url(),
+            "https://iam.test.cloud.ibm.com/identity/token"
+        );
+    }
+
+    #[test]
+    fn test_staging_and_local_server_endpoints() {
+        assert_eq!(
+            Endpoints::staging().get_token_url(),
+            "https://iam.test.cloud.ibm.com/identity/token"
+        );
+
+        let endpoints = Endpoints::local_server("http://127.0.0.1:9000");
+        assert_eq!(
+            endpoints.get_configuration_url("us-south", "guid"),
+            "http://127.0.0.1:9000/apprapp/feature/v1/instances/guid/config"
+        );
+        assert_eq!(
+            endpoints.get_websocket_url("us-south"),
+            "ws://127.0.0.1:9000/apprapp/wsfeature"
+        );
+        assert_eq!(
+            endpoints.get_token_url(),
+            "http://127.0.0.1:9000/identity/token"
+        );
+    }
+}
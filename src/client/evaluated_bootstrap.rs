@@ -0,0 +1,131 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Value;
+
+/// Features and properties already evaluated for one entity.
+///
+/// The document contains only the resulting values, not the segments or targeting rules
+/// used to compute them, so it can be handed to browsers or mobile apps. Use
+/// [`AppConfigurationEvaluated`](crate::AppConfigurationEvaluated) to read it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvaluatedBootstrap {
+    /// Version of the configuration used to evaluate the values. It changes whenever
+    /// the configuration changes.
+    pub version: String,
+    /// ID of the entity the values were evaluated for.
+    pub entity_id: String,
+    /// Evaluated features, by feature id.
+    pub features: HashMap<String, EvaluatedFeature>,
+    /// Evaluated properties, by property id.
+    pub properties: HashMap<String, EvaluatedProperty>,
+}
+
+/// A feature in an [`EvaluatedBootstrap`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvaluatedFeature {
+    /// Name of the feature.
+    pub name: String,
+    /// Whether the feature is enabled.
+    pub enabled: bool,
+    /// Value of the feature for the entity. Serialized as a plain JSON `value` and its
+    /// [`Value`] variant in `type`, next to the other fields.
+    #[serde(flatten, with = "typed_value")]
+    pub value: Value,
+}
+
+/// A property in an [`EvaluatedBootstrap`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvaluatedProperty {
+    /// Name of the property.
+    pub name: String,
+    /// Value of the property for the entity. Serialized as a plain JSON `value` and its
+    /// [`Value`] variant in `type`, next to the other fields.
+    #[serde(flatten, with = "typed_value")]
+    pub value: Value,
+}
+
+/// Values are written as plain JSON values next to a `type` field with their [`Value`]
+/// variant, so they are read back exactly as the client evaluated them (e.g. a JSON
+/// string stays [`Value::Structured`], a small `u64` stays [`Value::UInt64`]).
+mod typed_value {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::Value;
+
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum ValueType {
+        Float64,
+        UInt64,
+        Int64,
+        String,
+        Boolean,
+        Structured,
+    }
+
+    #[derive(Serialize)]
+    struct TypedValueRef<'a> {
+        value: &'a Value,
+        #[serde(rename = "type")]
+        value_type: ValueType,
+    }
+
+    #[derive(Deserialize)]
+    struct TypedValue {
+        value: serde_json::Value,
+        // Documents without type information are read as untyped values.
+        #[serde(rename = "type", default)]
+        value_type: Option<ValueType>,
+    }
+
+    pub(super) fn serialize<S: Serializer>(
+        value: &Value,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let value_type = match value {
+            Value::Float64(_) => ValueType::Float64,
+            Value::UInt64(_) => ValueType::UInt64,
+            Value::Int64(_) => ValueType::Int64,
+            Value::String(_) => ValueType::String,
+            Value::Boolean(_) => ValueType::Boolean,
+            Value::Structured(_) => ValueType::Structured,
+        };
+        TypedValueRef { value, value_type }.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Value, D::Error> {
+        let TypedValue { value, value_type } = TypedValue::deserialize(deserializer)?;
+        let Some(value_type) = value_type else {
+            return Value::deserialize(value).map_err(D::Error::custom);
+        };
+        let typed = match value_type {
+            ValueType::Float64 => value.as_f64().map(Value::Float64),
+            ValueType::UInt64 => value.as_u64().map(Value::UInt64),
+            ValueType::Int64 => value.as_i64().map(Value::Int64),
+            ValueType::String => value.as_str().map(|v| Value::String(v.to_string())),
+            ValueType::Boolean => value.as_bool().map(Value::Boolean),
+            ValueType::Structured => Some(Value::Structured(value.clone())),
+        };
+        typed
+            .ok_or_else(|| D::Error::custom(format!("value {value} is not of type {value_type:?}")))
+    }
+}
@@ -15,6 +15,7 @@
 use std::io::Cursor;
 
 use murmur3::murmur3_32;
+use serde::de::DeserializeOwned;
 
 use crate::entity::Entity;
 use crate::{Feature, Value};
@@ -52,8 +53,19 @@ impl<'a> Feature for FeatureProxy<'a> {
         self.client.get_feature(&self.feature_id)?.get_value(entity)
     }
 
-    fn get_value_into<T: TryFrom<Value, Error = crate::Error>>(&self, entity: &impl Entity) -> crate::errors::Result<T> {
-        self.client.get_feature(&self.feature_id)?.get_value_into(entity)
+    fn get_value_into<T: TryFrom<Value, Error = crate::Error>>(
+        &self,
+        entity: &impl Entity,
+    ) -> crate::errors::Result<T> {
+        self.client
+            .get_feature(&self.feature_id)?
+            .get_value_into(entity)
+    }
+
+    fn get_value_as<T: DeserializeOwned>(&self, entity: &impl Entity) -> crate::errors::Result<T> {
+        self.client
+            .get_feature(&self.feature_id)?
+            .get_value_as(entity)
     }
 }
 
@@ -12,37 +12,113 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use serde::de::DeserializeOwned;
+
 use crate::entity::Entity;
+use crate::evaluation::{EvaluationDetails, EvaluationReason};
 use crate::value::Value;
 use crate::Feature;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
 
 use super::feature_proxy::random_value;
+use super::metering::{Metering, MeteringHandle};
+use super::metrics;
+use crate::hooks::{Hooks, ResourceKind};
 use crate::segment_evaluation::find_applicable_segment_rule_for_entity;
 
-use crate::errors::Result;
+use crate::errors::{Error, Result};
+use crate::models::TargetingRule;
 
 /// Provides a snapshot of a [`Feature`].
 #[derive(Debug)]
 pub struct FeatureSnapshot {
     feature: crate::models::Feature,
     segments: HashMap<String, crate::models::Segment>,
+    metering: Option<Arc<Metering>>,
+    hooks: Hooks,
 }
 
 impl FeatureSnapshot {
     pub(crate) fn new(
         feature: crate::models::Feature,
         segments: HashMap<String, crate::models::Segment>,
+    ) -> Self {
+        Self {
+            feature,
+            segments,
+            metering: None,
+            hooks: Hooks::default(),
+        }
+    }
+
+    /// Records the evaluations of this snapshot in the client usage, if enabled.
+    pub(crate) fn with_metering(mut self, metering: Option<&MeteringHandle>) -> Self {
+        self.metering = metering.map(|handle| handle.metering.clone());
+        self
+    }
+
+    /// Calls the client hooks around the evaluations of this snapshot.
+    pub(crate) fn with_hooks(mut self, hooks: &Hooks) -> Self {
+        self.hooks = hooks.clone();
+        self
+    }
+
+    /// Evaluates the feature for the given [`Entity`] and returns the resulting value
+    /// together with the reason, the matched targeting rule and segment, and the rollout
+    /// percentage that was applied.
+    pub fn evaluate_details(&self, entity: &impl Entity) -> Result<EvaluationDetails> {
+        let details = self.hooks.run(
+            ResourceKind::Feature,
+            &self.feature.feature_id,
+            entity,
+            || FeatureEvaluation::new(&self.feature, &self.segments).evaluate_details(entity),
+        )?;
+        if let Some(metering) = &self.metering {
+            metering.record_feature(
+                &self.feature.feature_id,
+                entity.get_id(),
+                details.segment_id.as_deref(),
+            );
+        }
+        Ok(details)
+    }
+
+    fn should_rollout(rollout_percentage: u32, entity: &impl Entity, feature_id: &str) -> bool {
+        let tag = format!("{}:{}", entity.get_id(), feature_id);
+        rollout_percentage == 100 || random_value(&tag) < rollout_percentage
+    }
+}
+
+/// Evaluates a feature borrowing its data, so it can be used directly on a
+/// [`ConfigurationSnapshot`](super::cache::ConfigurationSnapshot).
+pub(crate) struct FeatureEvaluation<'a> {
+    feature: &'a crate::models::Feature,
+    segments: &'a HashMap<String, crate::models::Segment>,
+}
+
+impl<'a> FeatureEvaluation<'a> {
+    pub(crate) fn new(
+        feature: &'a crate::models::Feature,
+        segments: &'a HashMap<String, crate::models::Segment>,
     ) -> Self {
         Self { feature, segments }
     }
 
-    fn evaluate_feature_for_entity(
-        &self,
-        entity: &impl Entity,
-    ) -> Result<crate::models::ConfigValue> {
+    pub(crate) fn evaluate_details(&self, entity: &impl Entity) -> Result<EvaluationDetails> {
+        let _span = tracing::trace_span!("evaluate_feature", feature_id = %self.feature.feature_id)
+            .entered();
+        let started = Instant::now();
+        let result = self.evaluate(entity);
+        metrics::record_evaluation("feature", &self.feature.feature_id, started, &result);
+        result
+    }
+
+    fn evaluate(&self, entity: &impl Entity) -> Result<EvaluationDetails> {
         if !self.feature.enabled {
-            return Ok(self.feature.disabled_value.clone());
+            let value = self.to_value(self.feature.disabled_value.clone())?;
+            return Ok(EvaluationDetails::new(value, EvaluationReason::Disabled));
         }
 
         if self.feature.segment_rules.is_empty() || entity.get_attributes().is_empty() {
@@ -51,54 +127,76 @@ impl FeatureSnapshot {
         }
 
         match find_applicable_segment_rule_for_entity(
-            &self.segments,
+            self.segments,
             self.feature.segment_rules.clone().into_iter(),
             entity,
         )? {
-            Some(segment_rule) => {
-                // Get rollout percentage
-                let rollout_percentage = match segment_rule.rollout_percentage {
-                    Some(value) => {
-                        if value.is_default() {
-                            self.feature.rollout_percentage
-                        } else {
-                            u32::try_from(value.as_u64().expect("Rollout value is not u64."))
-                                .expect("Invalid rollout value. Could not convert to u32.")
-                        }
-                    }
-                    None => panic!("Rollout value is missing."),
-                };
+            Some((segment_rule, segment_id)) => {
+                let rollout_percentage = self.get_rollout_percentage(&segment_rule)?;
 
                 // Should rollout?
-                if Self::should_rollout(rollout_percentage, entity, &self.feature.feature_id) {
-                    if segment_rule.value.is_default() {
-                        Ok(self.feature.enabled_value.clone())
+                let details = if FeatureSnapshot::should_rollout(
+                    rollout_percentage,
+                    entity,
+                    &self.feature.feature_id,
+                ) {
+                    let value = if segment_rule.value.is_default() {
+                        self.feature.enabled_value.clone()
                     } else {
-                        Ok(segment_rule.value)
-                    }
+                        segment_rule.value
+                    };
+                    EvaluationDetails::new(self.to_value(value)?, EvaluationReason::TargetingMatch)
                 } else {
-                    Ok(self.feature.disabled_value.clone())
-                }
+                    let value = self.to_value(self.feature.disabled_value.clone())?;
+                    EvaluationDetails::new(value, EvaluationReason::RolloutExcluded)
+                };
+                Ok(details
+                    .with_targeting_rule(segment_rule.order, segment_id)
+                    .with_rollout_percentage(rollout_percentage))
             }
             None => self.use_rollout_percentage_to_get_value_from_feature_directly(entity),
         }
     }
 
-    fn should_rollout(rollout_percentage: u32, entity: &impl Entity, feature_id: &str) -> bool {
-        let tag = format!("{}:{}", entity.get_id(), feature_id);
-        rollout_percentage == 100 || random_value(&tag) < rollout_percentage
+    /// Returns the rollout percentage to apply when `segment_rule` matches. Rules without
+    /// a rollout percentage (lite plan) or with `$default` use the feature's one.
+    fn get_rollout_percentage(&self, segment_rule: &TargetingRule) -> Result<u32> {
+        match &segment_rule.rollout_percentage {
+            None => Ok(self.feature.rollout_percentage),
+            Some(value) if value.is_default() => Ok(self.feature.rollout_percentage),
+            Some(value) => value
+                .as_u64()
+                .filter(|percentage| *percentage <= 100)
+                .map(|percentage| percentage as u32)
+                .ok_or_else(|| Error::InvalidRolloutPercentage {
+                    feature_id: self.feature.feature_id.clone(),
+                    rule_order: segment_rule.order,
+                    value: value.to_string(),
+                }),
+        }
+    }
+
+    fn to_value(&self, value: crate::models::ConfigValue) -> Result<Value> {
+        (self.feature.kind, self.feature.format, value).try_into()
     }
 
     fn use_rollout_percentage_to_get_value_from_feature_directly(
         &self,
         entity: &impl Entity,
-    ) -> Result<crate::models::ConfigValue> {
+    ) -> Result<EvaluationDetails> {
         let rollout_percentage = self.feature.rollout_percentage;
-        if Self::should_rollout(rollout_percentage, entity, &self.feature.feature_id) {
-            Ok(self.feature.enabled_value.clone())
+        let details = if FeatureSnapshot::should_rollout(
+            rollout_percentage,
+            entity,
+            &self.feature.feature_id,
+        ) {
+            let value = self.to_value(self.feature.enabled_value.clone())?;
+            EvaluationDetails::new(value, EvaluationReason::Default)
         } else {
-            Ok(self.feature.disabled_value.clone())
-        }
+            let value = self.to_value(self.feature.disabled_value.clone())?;
+            EvaluationDetails::new(value, EvaluationReason::RolloutExcluded)
+        };
+        Ok(details.with_rollout_percentage(rollout_percentage))
     }
 }
 
@@ -112,21 +210,27 @@ impl Feature for FeatureSnapshot {
     }
 
     fn get_value(&self, entity: &impl Entity) -> Result<Value> {
-        let model_value = self.evaluate_feature_for_entity(entity)?;
-        (self.feature.kind, model_value).try_into()
+        Ok(self.evaluate_details(entity)?.value)
     }
 
-    fn get_value_into<T: TryFrom<Value, Error = crate::Error>>(&self, entity: &impl Entity) -> Result<T> {
+    fn get_value_into<T: TryFrom<Value, Error = crate::Error>>(
+        &self,
+        entity: &impl Entity,
+    ) -> Result<T> {
         let value = self.get_value(entity)?;
         value.try_into()
     }
+
+    fn get_value_as<T: DeserializeOwned>(&self, entity: &impl Entity) -> Result<T> {
+        self.get_value(entity)?.deserialize_into()
+    }
 }
 
 #[cfg(test)]
 pub mod tests {
 
     use super::*;
-    use crate::models::{ConfigValue, Segment, SegmentRule, Segments, TargetingRule, ValueKind};
+    use crate::models::{ConfigValue, Segment, SegmentRule, Segments, ValueKind};
     use rstest::rstest;
 
     #[rstest]
@@ -168,7 +272,7 @@ pub mod tests {
             name: "F1".to_string(),
             feature_id: "f1".to_string(),
             kind: ValueKind::Numeric,
-            _format: None,
+            format: None,
             enabled_value: ConfigValue(serde_json::Value::Number((-42).into())),
             disabled_value: ConfigValue(serde_json::Value::Number((2).into())),
             segment_rules,
@@ -209,7 +313,7 @@ pub mod tests {
             name: "F1".to_string(),
             feature_id: "f1".to_string(),
             kind: ValueKind::Numeric,
-            _format: None,
+            format: None,
             enabled_value: ConfigValue(serde_json::Value::Number((-42).into())),
             disabled_value: ConfigValue(serde_json::Value::Number((2).into())),
             segment_rules: Vec::new(),
@@ -231,7 +335,7 @@ pub mod tests {
             name: "F1".to_string(),
             feature_id: "f1".to_string(),
             kind: ValueKind::Numeric,
-            _format: None,
+            format: None,
             enabled_value: ConfigValue(serde_json::Value::Number((-42).into())),
             disabled_value: ConfigValue(serde_json::Value::Number((2).into())),
             segment_rules: vec![TargetingRule {
@@ -258,6 +362,7 @@ pub mod tests {
                         attribute_name: "name".into(),
                         operator: "is".into(),
                         values: vec!["heinz".into()],
+                        compiled_values: None,
                     }],
                 },
             )]),
@@ -291,6 +396,109 @@ pub mod tests {
         assert!(matches!(value, Value::Int64(ref v) if v == &(-42)));
     }
 
+    // The evaluation details explain which path of the evaluation produced the value.
+    #[test]
+    fn test_evaluate_details() {
+        let inner_feature = crate::models::Feature {
+            name: "F1".to_string(),
+            feature_id: "f1".to_string(),
+            kind: ValueKind::Numeric,
+            format: None,
+            enabled_value: ConfigValue(serde_json::Value::Number((-42).into())),
+            disabled_value: ConfigValue(serde_json::Value::Number((2).into())),
+            segment_rules: vec![TargetingRule {
+                rules: vec![Segments {
+                    segments: vec!["some_segment_id".into()],
+                }],
+                value: ConfigValue(serde_json::Value::Number((-48).into())),
+                order: 3,
+                rollout_percentage: Some(ConfigValue(serde_json::Value::Number((50).into()))),
+            }],
+            enabled: true,
+            rollout_percentage: 50,
+        };
+        let segments = HashMap::from([(
+            "some_segment_id".into(),
+            Segment {
+                _name: "".into(),
+                segment_id: "".into(),
+                _description: "".into(),
+                _tags: None,
+                rules: vec![SegmentRule {
+                    attribute_name: "name".into(),
+                    operator: "is".into(),
+                    values: vec!["heinz".into()],
+                    compiled_values: None,
+                }],
+            },
+        )]);
+        let feature = FeatureSnapshot::new(inner_feature.clone(), segments.clone());
+
+        // matching the segment + rollout allowed
+        let entity = crate::tests::GenericEntity {
+            id: "a2".into(),
+            attributes: HashMap::from([("name".into(), Value::from("heinz".to_string()))]),
+        };
+        let details = feature.evaluate_details(&entity).unwrap();
+        assert_eq!(details.value, Value::Int64(-48));
+        assert_eq!(details.reason, EvaluationReason::TargetingMatch);
+        assert_eq!(details.rule_order, Some(3));
+        assert_eq!(details.segment_id.as_deref(), Some("some_segment_id"));
+        assert_eq!(details.rollout_percentage, Some(50));
+
+        // matching the segment + rollout disallowed
+        let entity = crate::tests::GenericEntity {
+            id: "a1".into(),
+            attributes: HashMap::from([("name".into(), Value::from("heinz".to_string()))]),
+        };
+        let details = feature.evaluate_details(&entity).unwrap();
+        assert_eq!(details.value, Value::Int64(2));
+        assert_eq!(details.reason, EvaluationReason::RolloutExcluded);
+        assert_eq!(details.rule_order, Some(3));
+        assert_eq!(details.segment_id.as_deref(), Some("some_segment_id"));
+        assert_eq!(details.rollout_percentage, Some(50));
+
+        // not matching the segment + rollout allowed
+        let entity = crate::tests::GenericEntity {
+            id: "a2".into(),
+            attributes: HashMap::from([("name".into(), Value::from("heinzz".to_string()))]),
+        };
+        let details = feature.evaluate_details(&entity).unwrap();
+        assert_eq!(details.value, Value::Int64(-42));
+        assert_eq!(details.reason, EvaluationReason::Default);
+        assert_eq!(details.rule_order, None);
+        assert_eq!(details.segment_id, None);
+        assert_eq!(details.rollout_percentage, Some(50));
+
+        // not matching the segment + rollout disallowed
+        let entity = crate::tests::GenericEntity {
+            id: "a1".into(),
+            attributes: HashMap::from([("name".into(), Value::from("heinzz".to_string()))]),
+        };
+        let details = feature.evaluate_details(&entity).unwrap();
+        assert_eq!(details.value, Value::Int64(2));
+        assert_eq!(details.reason, EvaluationReason::RolloutExcluded);
+        assert_eq!(details.segment_id, None);
+
+        // disabled feature
+        let feature = FeatureSnapshot::new(
+            crate::models::Feature {
+                enabled: false,
+                ..inner_feature
+            },
+            segments,
+        );
+        let entity = crate::tests::GenericEntity {
+            id: "a2".into(),
+            attributes: HashMap::from([("name".into(), Value::from("heinz".to_string()))]),
+        };
+        let details = feature.evaluate_details(&entity).unwrap();
+        assert_eq!(
+            details,
+            EvaluationDetails::new(Value::Int64(2), EvaluationReason::Disabled)
+        );
+    }
+
     // The matched segment rule's value has a "$default" value.
     // In this case, the feature's enabled value should be used whenever the rule matches.
     #[test]
@@ -299,7 +507,7 @@ pub mod tests {
             name: "F1".to_string(),
             feature_id: "f1".to_string(),
             kind: ValueKind::Numeric,
-            _format: None,
+            format: None,
             enabled_value: ConfigValue(serde_json::Value::Number((-42).into())),
             disabled_value: ConfigValue(serde_json::Value::Number((2).into())),
             segment_rules: vec![TargetingRule {
@@ -326,6 +534,7 @@ pub mod tests {
                         attribute_name: "name".into(),
                         operator: "is".into(),
                         values: vec!["heinz".into()],
+                        compiled_values: None,
                     }],
                 },
             )]),
@@ -349,7 +558,7 @@ pub mod tests {
             name: "F1".to_string(),
             feature_id: "f1".to_string(),
             kind: ValueKind::Numeric,
-            _format: None,
+            format: None,
             enabled_value: ConfigValue(serde_json::Value::Number((-42).into())),
             disabled_value: ConfigValue(serde_json::Value::Number((2).into())),
             segment_rules: vec![TargetingRule {
@@ -376,6 +585,7 @@ pub mod tests {
                         attribute_name: "name".into(),
                         operator: "is".into(),
                         values: vec!["heinz".into()],
+                        compiled_values: None,
                     }],
                 },
             )]),
@@ -391,13 +601,106 @@ pub mod tests {
         assert!(matches!(value, Value::Int64(ref v) if v == &2));
     }
 
+    fn feature_with_segment_rollout(
+        rollout_percentage: Option<ConfigValue>,
+        feature_rollout_percentage: u32,
+    ) -> FeatureSnapshot {
+        let inner_feature = crate::models::Feature {
+            name: "F1".to_string(),
+            feature_id: "f1".to_string(),
+            kind: ValueKind::Numeric,
+            format: None,
+            enabled_value: ConfigValue(serde_json::Value::Number((-42).into())),
+            disabled_value: ConfigValue(serde_json::Value::Number((2).into())),
+            segment_rules: vec![TargetingRule {
+                rules: vec![Segments {
+                    segments: vec!["some_segment_id".into()],
+                }],
+                value: ConfigValue(serde_json::Value::Number((48).into())),
+                order: 0,
+                rollout_percentage,
+            }],
+            enabled: true,
+            rollout_percentage: feature_rollout_percentage,
+        };
+        FeatureSnapshot::new(
+            inner_feature,
+            HashMap::from([(
+                "some_segment_id".into(),
+                Segment {
+                    _name: "".into(),
+                    segment_id: "".into(),
+                    _description: "".into(),
+                    _tags: None,
+                    rules: vec![SegmentRule {
+                        attribute_name: "name".into(),
+                        operator: "is".into(),
+                        values: vec!["heinz".into()],
+                        compiled_values: None,
+                    }],
+                },
+            )]),
+        )
+    }
+
+    // The matched segment rule has no rollout percentage (as in lite plan configurations).
+    // In this case, the feature's rollout percentage should be used.
+    #[rstest]
+    #[case(100, 48)]
+    #[case(0, 2)]
+    fn test_get_value_matching_segment_rollout_missing(
+        #[case] feature_rollout_percentage: u32,
+        #[case] expected: i64,
+    ) {
+        let feature = feature_with_segment_rollout(None, feature_rollout_percentage);
+        let entity = crate::tests::GenericEntity {
+            id: "a2".into(),
+            attributes: HashMap::from([("name".into(), Value::from("heinz".to_string()))]),
+        };
+
+        let details = feature.evaluate_details(&entity).unwrap();
+        assert_eq!(details.value, Value::Int64(expected));
+        assert_eq!(details.rollout_percentage, Some(feature_rollout_percentage));
+    }
+
+    // Rollout percentages which are not numbers between 0 and 100 fail the evaluation.
+    #[rstest]
+    #[case(serde_json::json!(101))]
+    #[case(serde_json::json!(-1))]
+    #[case(serde_json::json!(50.5))]
+    #[case(serde_json::json!("fifty"))]
+    #[case(serde_json::json!(u64::MAX))]
+    fn test_get_value_matching_segment_rollout_invalid(
+        #[case] rollout_percentage: serde_json::Value,
+    ) {
+        let feature =
+            feature_with_segment_rollout(Some(ConfigValue(rollout_percentage.clone())), 100);
+        let entity = crate::tests::GenericEntity {
+            id: "a2".into(),
+            attributes: HashMap::from([("name".into(), Value::from("heinz".to_string()))]),
+        };
+
+        let error = feature.get_value(&entity).unwrap_err();
+        let Error::InvalidRolloutPercentage {
+            feature_id,
+            rule_order,
+            value,
+        } = error
+        else {
+            panic!("Error type mismatch!");
+        };
+        assert_eq!(feature_id, "f1");
+        assert_eq!(rule_order, 0);
+        assert_eq!(value, rollout_percentage.to_string());
+    }
+
     #[test]
     fn test_get_value_segment_rule_ordering() {
         let inner_feature = crate::models::Feature {
             name: "F1".to_string(),
             feature_id: "f1".to_string(),
             kind: ValueKind::Numeric,
-            _format: None,
+            format: None,
             enabled_value: ConfigValue(serde_json::Value::Number((-42).into())),
             disabled_value: ConfigValue(serde_json::Value::Number((2).into())),
             segment_rules: vec![
@@ -435,6 +738,7 @@ pub mod tests {
                             attribute_name: "name".into(),
                             operator: "is".into(),
                             values: vec!["heinz".into()],
+                            compiled_values: None,
                         }],
                     },
                 ),
@@ -449,6 +753,7 @@ pub mod tests {
                             attribute_name: "name".into(),
                             operator: "is".into(),
                             values: vec!["heinz".into()],
+                            compiled_values: None,
                         }],
                     },
                 ),
@@ -13,33 +13,81 @@
 // limitations under the License.
 
 use std::collections::HashMap;
-use std::net::TcpStream;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
 
 use reqwest::blocking::Client;
 use serde::Deserialize;
 use tungstenite::client::IntoClientRequest;
-use tungstenite::handshake::client::Response;
+use tungstenite::handshake::client::{Request, Response};
+use tungstenite::handshake::HandshakeError;
 use tungstenite::stream::MaybeTlsStream;
-use tungstenite::{connect, WebSocket};
+use tungstenite::WebSocket;
 use url::Url;
 
-use crate::errors::{Error, Result};
+use crate::client::options::ClientOptions;
+use crate::client::transport::ServiceInstance;
+use crate::errors::{DeserializationError, Error, Result};
 use crate::models;
 
 #[derive(Deserialize)]
-struct AccessTokenResponse {
-    access_token: String,
+pub(crate) struct AccessTokenResponse {
+    pub(crate) access_token: String,
+    /// Lifetime of the token in seconds
+    pub(crate) expires_in: Option<u64>,
+    /// Expiration time of the token (seconds since the Unix epoch)
+    pub(crate) expiration: Option<u64>,
 }
 
-pub fn get_base_url(region: &str, guid: &str) -> String {
-    format!("https://{region}.apprapp.cloud.ibm.com/apprapp/feature/v1/instances/{guid}/config")
+/// Settings applied to every HTTP request sent to the server.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HttpOptions {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) proxy: Option<String>,
 }
 
-pub fn get_ws_url(region: &str) -> String {
-    format!("wss://{region}.apprapp.cloud.ibm.com/apprapp/wsfeature")
+impl HttpOptions {
+    pub(crate) fn new(options: &ClientOptions) -> Self {
+        Self {
+            timeout: options.timeout,
+            connect_timeout: options.connect_timeout,
+            proxy: options.proxy.clone(),
+        }
+    }
+
+    pub(crate) fn blocking_client(&self) -> Result<Client> {
+        let mut builder = Client::builder();
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        Ok(builder.build()?)
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) fn async_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        Ok(builder.build()?)
+    }
 }
 
-pub fn get_access_token(apikey: &str) -> Result<String> {
+pub(crate) fn get_access_token_form_data(apikey: &str) -> HashMap<String, String> {
     let mut form_data = HashMap::new();
     form_data.insert("reponse_type".to_string(), "cloud_iam".to_string());
     form_data.insert(
@@ -47,64 +95,94 @@ pub fn get_access_token(apikey: &str) -> Result<String> {
         "urn:ibm:params:oauth:grant-type:apikey".to_string(),
     );
     form_data.insert("apikey".to_string(), apikey.to_string());
+    form_data
+}
+
+/// Parses the body of the response sent by IAM.
+pub(crate) fn parse_access_token(body: String) -> Result<AccessTokenResponse> {
+    serde_json::from_str(&body).map_err(|e| {
+        DeserializationError {
+            string: body,
+            source: e.into(),
+        }
+        .into()
+    })
+}
+
+/// Parses the body of the response with the configuration.
+pub(crate) fn parse_configuration(body: &str) -> Result<models::Configuration> {
+    serde_json::from_str(body).map_err(Error::ConfigurationParseError)
+}
 
-    let client = Client::new();
+pub(crate) fn get_access_token(
+    apikey: &str,
+    token_url: &str,
+    http_options: &HttpOptions,
+) -> Result<String> {
+    let form_data = get_access_token_form_data(apikey);
+    let client = http_options.blocking_client()?;
     Ok(client
-        .post("https://iam.cloud.ibm.com/identity/token")
+        .post(token_url)
         .header("Accept", "application/json")
         .form(&form_data)
-        .send()
-        .map_err(Error::ReqwestError)?
-        .json::<AccessTokenResponse>()
-        .map_err(Error::ReqwestError)? // FIXME: This is a deserialization error (extract it from Reqwest)
-        .access_token)
+        .send()?
+        .error_for_status()?
+        .text()?)
 }
 
-pub fn get_configuration(
+pub(crate) fn get_configuration(
     access_token: &str,
-    region: &str,
-    guid: &str,
-    collection_id: &str,
-    environment_id: &str,
-) -> Result<models::Configuration> {
-    let client = Client::new();
-    let url = get_base_url(region, guid);
-    client
-        .get(&url)
+    instance: &ServiceInstance,
+    http_options: &HttpOptions,
+) -> Result<String> {
+    let client = http_options.blocking_client()?;
+    Ok(client
+        .get(instance.configuration_url())
         .query(&[
             ("action", "sdkConfig"),
-            ("collection_id", collection_id),
-            ("environment_id", environment_id),
+            ("collection_id", instance.collection_id()),
+            ("environment_id", instance.environment_id()),
         ])
         .header("Accept", "application/json")
         .header("User-Agent", "appconfiguration-rust-sdk/0.0.1")
         .bearer_auth(access_token)
-        .send()
-        .map_err(Error::ReqwestError)?
-        .json()
-        .map_err(Error::ReqwestError) // FIXME: This is a deserialization error (extract it from Reqwest)
+        .send()?
+        .error_for_status()?
+        .text()?)
 }
 
-pub fn get_configuration_monitoring_websocket(
+pub(crate) fn send_usage(
     access_token: &str,
-    region: &str,
-    guid: &str,
-    collection_id: &str,
-    environment_id: &str,
-) -> Result<(WebSocket<MaybeTlsStream<TcpStream>>, Response)> {
-    let url = get_ws_url(region);
+    instance: &ServiceInstance,
+    body: &str,
+    http_options: &HttpOptions,
+) -> Result<()> {
+    let client = http_options.blocking_client()?;
+    client
+        .post(instance.usage_url())
+        .header("Content-Type", "application/json")
+        .header("User-Agent", "appconfiguration-rust-sdk/0.0.1")
+        .bearer_auth(access_token)
+        .body(body.to_string())
+        .send()?
+        .error_for_status()?;
+    Ok(())
+}
+
+pub(crate) fn get_configuration_monitoring_websocket_request(
+    access_token: &str,
+    instance: &ServiceInstance,
+) -> Result<Request> {
+    let url = instance.websocket_url();
     let mut url = Url::parse(&url)
         .map_err(|e| Error::Other(format!("Cannot parse '{}' as URL: {}", url, e)))?;
 
     url.query_pairs_mut()
-        .append_pair("instance_id", guid)
-        .append_pair("collection_id", collection_id)
-        .append_pair("environment_id", environment_id);
-
-    let mut request = url
-        .as_str()
-        .into_client_request()
-        .map_err(Error::TungsteniteError)?;
+        .append_pair("instance_id", instance.guid())
+        .append_pair("collection_id", instance.collection_id())
+        .append_pair("environment_id", instance.environment_id());
+
+    let mut request = url.as_str().into_client_request()?;
     let headers = request.headers_mut();
     headers.insert(
         "User-Agent",
@@ -118,6 +196,255 @@ pub fn get_configuration_monitoring_websocket(
             .parse()
             .map_err(|_| Error::Other("Invalid header value for 'Authorization'".to_string()))?,
     );
+    Ok(request)
+}
+
+pub(crate) fn get_configuration_monitoring_websocket(
+    access_token: &str,
+    instance: &ServiceInstance,
+    http_options: &HttpOptions,
+) -> Result<(WebSocket<MaybeTlsStream<TcpStream>>, Response)> {
+    let request = get_configuration_monitoring_websocket_request(access_token, instance)?;
+    let (host, port) = websocket_address(&request)?;
+    let stream = match &http_options.proxy {
+        Some(proxy) => {
+            let proxy = WebSocketProxy::parse(proxy)?;
+            let mut stream = connect_tcp(&proxy.host, proxy.port, http_options.connect_timeout)?;
+            stream.set_read_timeout(http_options.connect_timeout)?;
+            stream.write_all(proxy.connect_request(&host, port).as_bytes())?;
+            check_connect_response(&read_response_head(&mut stream)?)?;
+            stream.set_read_timeout(None)?;
+            stream
+        }
+        None => connect_tcp(&host, port, http_options.connect_timeout)?,
+    };
+    tungstenite::client_tls(request, stream).map_err(|e| match e {
+        HandshakeError::Failure(e) => e.into(),
+        HandshakeError::Interrupted(_) => Error::Other("Websocket handshake interrupted".into()),
+    })
+}
+
+/// Host and port the websocket `request` connects to.
+pub(crate) fn websocket_address(request: &Request) -> Result<(String, u16)> {
+    let uri = request.uri();
+    let host = uri
+        .host()
+        .ok_or_else(|| Error::Other(format!("No host in websocket URL '{uri}'")))?;
+    let default_port = match uri.scheme_str() {
+        Some("wss") => 443,
+        _ => 80,
+    };
+    Ok((host.to_string(), uri.port_u16().unwrap_or(default_port)))
+}
+
+fn connect_tcp(host: &str, port: u16, timeout: Option<Duration>) -> Result<TcpStream> {
+    let mut last_error =
+        io::Error::new(io::ErrorKind::NotFound, format!("Cannot resolve '{host}'"));
+    for address in (host, port).to_socket_addrs()? {
+        let stream = match timeout {
+            Some(timeout) => TcpStream::connect_timeout(&address, timeout),
+            None => TcpStream::connect(address),
+        };
+        match stream {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error.into())
+}
+
+/// Maximum length of the head of the response to a `CONNECT` request.
+pub(crate) const MAX_RESPONSE_HEAD: usize = 8 * 1024;
+
+fn read_response_head(stream: &mut TcpStream) -> Result<Vec<u8>> {
+    // Read byte by byte: the tunneled data right after the head belongs to the websocket
+    let mut head = Vec::new();
+    let mut byte = [0u8];
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() > MAX_RESPONSE_HEAD {
+            return Err(Error::Other("Response from the proxy is too long".into()));
+        }
+        stream.read_exact(&mut byte)?;
+        head.push(byte[0]);
+    }
+    Ok(head)
+}
+
+/// HTTP proxy the websocket is tunneled through, with an HTTP `CONNECT` request.
+#[derive(Debug)]
+pub(crate) struct WebSocketProxy {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    /// Value of the `Proxy-Authorization` header, if the proxy URL has credentials.
+    authorization: Option<String>,
+}
+
+impl WebSocketProxy {
+    /// Parses the proxy URL, only the `http` scheme is supported. As in `reqwest`, URLs
+    /// without scheme are `http` URLs.
+    pub(crate) fn parse(proxy: &str) -> Result<Self> {
+        let url = match proxy.contains("://") {
+            true => Url::parse(proxy),
+            false => Url::parse(&format!("http://{proxy}")),
+        }
+        .map_err(|e| Error::Other(format!("Cannot parse '{proxy}' as URL: {e}")))?;
+        if url.scheme() != "http" {
+            return Err(Error::Other(
+                "Only http proxies can tunnel the websocket receiving live-updates".into(),
+            ));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| Error::Other(format!("No host in proxy URL '{proxy}'")))?;
+        let authorization = (!url.username().is_empty()).then(|| {
+            let credentials = format!("{}:{}", url.username(), url.password().unwrap_or(""));
+            format!("Basic {}", base64_encode(credentials.as_bytes()))
+        });
+        Ok(Self {
+            host: host.to_string(),
+            port: url.port_or_known_default().unwrap_or(80),
+            authorization,
+        })
+    }
+
+    /// Request asking the proxy to open a tunnel to `host:port`.
+    pub(crate) fn connect_request(&self, host: &str, port: u16) -> String {
+        let mut request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+        if let Some(authorization) = &self.authorization {
+            request.push_str(&format!("Proxy-Authorization: {authorization}\r\n"));
+        }
+        request.push_str("\r\n");
+        request
+    }
+}
+
+/// Checks the head of the response to a `CONNECT` request: the tunnel is open if the
+/// status is `2xx`.
+pub(crate) fn check_connect_response(head: &[u8]) -> Result<()> {
+    let head = String::from_utf8_lossy(head);
+    let status_line = head.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') => Ok(()),
+        _ => Err(Error::Other(format!(
+            "The proxy did not open a tunnel for the websocket: '{status_line}'"
+        ))),
+    }
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::net::TcpListener;
+    use std::thread;
+
+    use tungstenite::Message;
+
+    use super::*;
+    use crate::client::endpoints::Endpoints;
+
+    pub(crate) fn instance() -> ServiceInstance {
+        let endpoints = Endpoints {
+            websocket_url: Some("ws://appconfig.test:1234".to_string()),
+            ..Default::default()
+        };
+        ServiceInstance::new(
+            "region",
+            "guid",
+            "environment_id",
+            "collection_id",
+            endpoints,
+        )
+    }
+
+    /// Starts a proxy that answers the `CONNECT` request with `response` and then acts
+    /// as the websocket server at the other end of the tunnel. Returns the proxy URL
+    /// and the received `CONNECT` request.
+    pub(crate) fn start_proxy(response: &'static str) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://user:secret@{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let head = read_response_head(&mut stream).unwrap();
+            stream.write_all(response.as_bytes()).unwrap();
+            if response.starts_with("HTTP/1.1 200") {
+                let mut socket = tungstenite::accept(stream).unwrap();
+                socket.send(Message::text("update")).unwrap();
+            }
+            String::from_utf8(head).unwrap()
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_websocket_through_proxy() {
+        let (proxy, handle) = start_proxy("HTTP/1.1 200 Connection established\r\n\r\n");
+        let http_options = HttpOptions {
+            proxy: Some(proxy),
+            connect_timeout: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+
+        let (mut socket, _) =
+            get_configuration_monitoring_websocket("token", &instance(), &http_options).unwrap();
+        assert_eq!(socket.read().unwrap(), Message::text("update"));
+
+        let request = handle.join().unwrap();
+        assert!(request.starts_with("CONNECT appconfig.test:1234 HTTP/1.1\r\n"));
+        assert!(request.contains("Proxy-Authorization: Basic dXNlcjpzZWNyZXQ=\r\n"));
+    }
+
+    #[test]
+    fn test_websocket_proxy_refuses_tunnel() {
+        let (proxy, handle) = start_proxy("HTTP/1.1 407 Proxy Authentication Required\r\n\r\n");
+        let http_options = HttpOptions {
+            proxy: Some(proxy),
+            ..Default::default()
+        };
+
+        let Error::Other(message) =
+            get_configuration_monitoring_websocket("token", &instance(), &http_options)
+                .unwrap_err()
+        else {
+            panic!("Error type mismatch!");
+        };
+        assert!(message.contains("407"));
+        handle.join().unwrap();
+    }
 
-    Ok(connect(request)?)
+    #[test]
+    fn test_websocket_proxy_url() {
+        let proxy = WebSocketProxy::parse("proxy.example.com:3128").unwrap();
+        assert_eq!(
+            (proxy.host.as_str(), proxy.port),
+            ("proxy.example.com", 3128)
+        );
+        assert_eq!(
+            proxy.connect_request("example.com", 443),
+            "CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n"
+        );
+        assert!(WebSocketProxy::parse("https://proxy.example.com").is_err());
+        assert_eq!(base64_encode(b"a"), "YQ==");
+        assert_eq!(base64_encode(b"ab"), "YWI=");
+        assert_eq!(base64_encode(b"abc"), "YWJj");
+    }
 }
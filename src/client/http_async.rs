@@ -0,0 +1,158 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Async counterparts of the functions in [`super::http`], built on `reqwest`'s
+//! async client and `tokio-tungstenite`.
+
+use std::future::Future;
+use std::io;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::handshake::client::Response;
+use tokio_tungstenite::{client_async_tls, MaybeTlsStream, WebSocketStream};
+
+use crate::client::http::{
+    check_connect_response, get_access_token_form_data,
+    get_configuration_monitoring_websocket_request, websocket_address, HttpOptions, WebSocketProxy,
+    MAX_RESPONSE_HEAD,
+};
+use crate::client::transport::ServiceInstance;
+use crate::errors::{Error, Result};
+
+pub(crate) async fn get_access_token(
+    apikey: &str,
+    token_url: &str,
+    http_options: &HttpOptions,
+) -> Result<String> {
+    let form_data = get_access_token_form_data(apikey);
+    let client = http_options.async_client()?;
+    Ok(client
+        .post(token_url)
+        .header("Accept", "application/json")
+        .form(&form_data)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?)
+}
+
+pub(crate) async fn get_configuration(
+    access_token: &str,
+    instance: &ServiceInstance,
+    http_options: &HttpOptions,
+) -> Result<String> {
+    let client = http_options.async_client()?;
+    Ok(client
+        .get(instance.configuration_url())
+        .query(&[
+            ("action", "sdkConfig"),
+            ("collection_id", instance.collection_id()),
+            ("environment_id", instance.environment_id()),
+        ])
+        .header("Accept", "application/json")
+        .header("User-Agent", "appconfiguration-rust-sdk/0.0.1")
+        .bearer_auth(access_token)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?)
+}
+
+pub(crate) async fn get_configuration_monitoring_websocket(
+    access_token: &str,
+    instance: &ServiceInstance,
+    http_options: &HttpOptions,
+) -> Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response)> {
+    let request = get_configuration_monitoring_websocket_request(access_token, instance)?;
+    let (host, port) = websocket_address(&request)?;
+    let stream = match &http_options.proxy {
+        Some(proxy) => {
+            let proxy = WebSocketProxy::parse(proxy)?;
+            with_timeout(http_options.connect_timeout, async {
+                let mut stream = TcpStream::connect((proxy.host.as_str(), proxy.port)).await?;
+                stream
+                    .write_all(proxy.connect_request(&host, port).as_bytes())
+                    .await?;
+                check_connect_response(&read_response_head(&mut stream).await?)?;
+                Ok(stream)
+            })
+            .await?
+        }
+        None => {
+            with_timeout(http_options.connect_timeout, async {
+                Ok(TcpStream::connect((host.as_str(), port)).await?)
+            })
+            .await?
+        }
+    };
+    Ok(client_async_tls(request, stream).await?)
+}
+
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future)
+            .await
+            .map_err(|_| Error::from(io::Error::from(io::ErrorKind::TimedOut)))?,
+        None => future.await,
+    }
+}
+
+async fn read_response_head(stream: &mut TcpStream) -> Result<Vec<u8>> {
+    // Read byte by byte: the tunneled data right after the head belongs to the websocket
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() > MAX_RESPONSE_HEAD {
+            return Err(Error::Other("Response from the proxy is too long".into()));
+        }
+        head.push(stream.read_u8().await?);
+    }
+    Ok(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_tungstenite::tungstenite::Message;
+
+    use super::*;
+    use crate::client::http::tests::{instance, start_proxy};
+
+    #[tokio::test]
+    async fn test_websocket_through_proxy() {
+        use futures_util::StreamExt;
+
+        let (proxy, handle) = start_proxy("HTTP/1.1 200 Connection established\r\n\r\n");
+        let http_options = HttpOptions {
+            proxy: Some(proxy),
+            connect_timeout: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+
+        let (mut socket, _) =
+            get_configuration_monitoring_websocket("token", &instance(), &http_options)
+                .await
+                .unwrap();
+        let message = socket.next().await.unwrap().unwrap();
+        assert_eq!(message, Message::text("update"));
+
+        let request = handle.join().unwrap();
+        assert!(request.starts_with("CONNECT appconfig.test:1234 HTTP/1.1\r\n"));
+    }
+}
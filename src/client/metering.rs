@@ -0,0 +1,261 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+
+use crate::client::context::ConnectionContext;
+use crate::client::transport::{ServiceInstance, Transport};
+use crate::errors::Result;
+
+/// Segment reported for the evaluations that didn't match any targeting rule.
+const NO_SEGMENT: &str = "$$null$$";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Resource {
+    Feature(String),
+    Property(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct UsageKey {
+    resource: Resource,
+    entity_id: String,
+    segment_id: Option<String>,
+}
+
+#[derive(Debug)]
+struct Usage {
+    count: u64,
+    evaluation_time: DateTime<Utc>,
+}
+
+/// Aggregates the evaluations of features and properties until they are sent to the server.
+#[derive(Debug, Default)]
+pub(crate) struct Metering {
+    usages: Mutex<HashMap<UsageKey, Usage>>,
+}
+
+impl Metering {
+    /// Records that `feature_id` was evaluated for `entity_id`, matching `segment_id`.
+    pub(crate) fn record_feature(
+        &self,
+        feature_id: &str,
+        entity_id: String,
+        segment_id: Option<&str>,
+    ) {
+        self.record(
+            Resource::Feature(feature_id.to_string()),
+            entity_id,
+            segment_id,
+        )
+    }
+
+    /// Records that `property_id` was evaluated for `entity_id`, matching `segment_id`.
+    pub(crate) fn record_property(
+        &self,
+        property_id: &str,
+        entity_id: String,
+        segment_id: Option<&str>,
+    ) {
+        self.record(
+            Resource::Property(property_id.to_string()),
+            entity_id,
+            segment_id,
+        )
+    }
+
+    fn record(&self, resource: Resource, entity_id: String, segment_id: Option<&str>) {
+        let key = UsageKey {
+            resource,
+            entity_id,
+            segment_id: segment_id.map(str::to_string),
+        };
+        // Metering must never break an evaluation: a poisoned lock only loses the usage.
+        if let Ok(mut usages) = self.usages.lock() {
+            let usage = usages.entry(key).or_insert(Usage {
+                count: 0,
+                evaluation_time: Utc::now(),
+            });
+            usage.count += 1;
+            usage.evaluation_time = Utc::now();
+        }
+    }
+
+    /// Removes the usage recorded so far and returns it as a report for `instance`,
+    /// or `None` if nothing was evaluated.
+    pub(crate) fn take_report(&self, instance: &ServiceInstance) -> Result<Option<UsageReport>> {
+        let usages = std::mem::take(&mut *self.usages.lock()?);
+        if usages.is_empty() {
+            return Ok(None);
+        }
+        let usages = usages
+            .into_iter()
+            .map(|(key, usage)| {
+                let (feature_id, property_id) = match key.resource {
+                    Resource::Feature(id) => (Some(id), None),
+                    Resource::Property(id) => (None, Some(id)),
+                };
+                UsageRecord {
+                    feature_id,
+                    property_id,
+                    entity_id: key.entity_id,
+                    segment_id: key.segment_id.unwrap_or_else(|| NO_SEGMENT.to_string()),
+                    evaluation_time: usage
+                        .evaluation_time
+                        .to_rfc3339_opts(SecondsFormat::Millis, true),
+                    count: usage.count,
+                }
+            })
+            .collect();
+        Ok(Some(UsageReport {
+            collection_id: instance.collection_id().to_string(),
+            environment_id: instance.environment_id().to_string(),
+            usages,
+        }))
+    }
+}
+
+/// Body of the requests sent to the usage endpoint.
+#[derive(Debug, Serialize)]
+pub(crate) struct UsageReport {
+    pub(crate) collection_id: String,
+    pub(crate) environment_id: String,
+    pub(crate) usages: Vec<UsageRecord>,
+}
+
+/// Number of evaluations of a feature or property for an entity and segment.
+#[derive(Debug, Serialize)]
+pub(crate) struct UsageRecord {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) feature_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) property_id: Option<String>,
+    pub(crate) entity_id: String,
+    pub(crate) segment_id: String,
+    /// Time of the last evaluation.
+    pub(crate) evaluation_time: String,
+    pub(crate) count: u64,
+}
+
+/// The [`Metering`] of a client and the thread sending its usage to the server.
+///
+/// The thread sends the usage every `interval` and, one last time, when this
+/// handle is dropped together with the client.
+#[derive(Debug)]
+pub(crate) struct MeteringHandle {
+    pub(crate) metering: Arc<Metering>,
+    _thread_terminator: Sender<()>,
+}
+
+impl MeteringHandle {
+    pub(crate) fn start<T: Transport>(
+        context: Arc<ConnectionContext<T>>,
+        interval: Duration,
+    ) -> Self {
+        let metering = Arc::new(Metering::default());
+        let (terminator, receiver) = std::sync::mpsc::channel();
+
+        let recorded = metering.clone();
+        thread::spawn(move || loop {
+            let finished = matches!(
+                receiver.recv_timeout(interval),
+                Err(RecvTimeoutError::Disconnected)
+            );
+            // The usage is dropped if it cannot be sent: counts are informative only.
+            let sent = recorded
+                .take_report(&context.instance)
+                .and_then(|report| match report {
+                    Some(report) => context.send_usage(&report),
+                    None => Ok(()),
+                });
+            if let Err(e) = sent {
+                if context.logging {
+                    tracing::warn!(
+                        parent: &context.span,
+                        error = %e,
+                        "Cannot send usage to the server"
+                    );
+                }
+            }
+            if finished {
+                return;
+            }
+        });
+
+        Self {
+            metering,
+            _thread_terminator: terminator,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::endpoints::Endpoints;
+
+    #[test]
+    fn test_take_report_aggregates_usage() {
+        let instance = ServiceInstance::new(
+            "us-south",
+            "guid",
+            "environment_id",
+            "collection_id",
+            Endpoints::default(),
+        );
+        let metering = Metering::default();
+        assert!(metering.take_report(&instance).unwrap().is_none());
+
+        metering.record_feature("f1", "a1".into(), None);
+        metering.record_feature("f1", "a1".into(), None);
+        metering.record_feature("f1", "a1".into(), Some("s1"));
+        metering.record_property("p1", "a2".into(), Some("s1"));
+
+        let report = metering.take_report(&instance).unwrap().unwrap();
+        assert_eq!(report.collection_id, "collection_id");
+        assert_eq!(report.environment_id, "environment_id");
+        let mut usages = report
+            .usages
+            .iter()
+            .map(|u| {
+                (
+                    u.feature_id.as_deref(),
+                    u.property_id.as_deref(),
+                    u.entity_id.as_str(),
+                    u.segment_id.as_str(),
+                    u.count,
+                )
+            })
+            .collect::<Vec<_>>();
+        usages.sort();
+        assert_eq!(
+            usages,
+            vec![
+                (None, Some("p1"), "a2", "s1", 1),
+                (Some("f1"), None, "a1", "$$null$$", 2),
+                (Some("f1"), None, "a1", "s1", 1),
+            ]
+        );
+
+        // Usage is sent only once
+        assert!(metering.take_report(&instance).unwrap().is_none());
+    }
+}
@@ -0,0 +1,161 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Metrics recorded through the [`metrics`](https://docs.rs/metrics) facade when the
+//! `metrics` feature is enabled. Without it, every function is a no-op.
+
+#[cfg(feature = "metrics")]
+mod recorder {
+    use std::time::{Duration, Instant};
+
+    use crate::client::transport::ServiceInstance;
+    use crate::errors::{EntityEvaluationError, Error, Result};
+    use crate::evaluation::{EvaluationDetails, EvaluationReason};
+    use crate::segment_evaluation::errors::SegmentEvaluationError;
+
+    /// Records the evaluation of the feature or property (`kind`) with the given `id`,
+    /// that started at `started`.
+    pub(crate) fn record_evaluation(
+        kind: &'static str,
+        id: &str,
+        started: Instant,
+        result: &Result<EvaluationDetails>,
+    ) {
+        metrics::histogram!(
+            "appconfiguration_evaluation_duration_seconds",
+            "kind" => kind,
+            "id" => id.to_string()
+        )
+        .record(started.elapsed());
+        match result {
+            Ok(details) => {
+                let reason = match details.reason {
+                    EvaluationReason::Disabled => "disabled",
+                    EvaluationReason::TargetingMatch => "targeting_match",
+                    EvaluationReason::RolloutExcluded => "rollout_excluded",
+                    EvaluationReason::Default => "default",
+                };
+                metrics::counter!(
+                    "appconfiguration_evaluations_total",
+                    "kind" => kind,
+                    "id" => id.to_string(),
+                    "reason" => reason
+                )
+                .increment(1);
+            }
+            Err(e) => {
+                metrics::counter!(
+                    "appconfiguration_evaluation_errors_total",
+                    "kind" => kind,
+                    "id" => id.to_string(),
+                    "error" => error_kind(e)
+                )
+                .increment(1);
+            }
+        }
+    }
+
+    /// Records a request for the configuration that started at `started`.
+    pub(crate) fn record_configuration_fetch(
+        instance: &ServiceInstance,
+        started: Instant,
+        succeeded: bool,
+    ) {
+        let labels = labels(instance);
+        metrics::histogram!(
+            "appconfiguration_configuration_fetch_duration_seconds",
+            &labels
+        )
+        .record(started.elapsed());
+        if !succeeded {
+            metrics::counter!(
+                "appconfiguration_configuration_fetch_failures_total",
+                &labels
+            )
+            .increment(1);
+        }
+    }
+
+    /// Records an attempt to open the connection receiving live-updates again.
+    pub(crate) fn record_reconnect(instance: &ServiceInstance, succeeded: bool) {
+        let mut labels = labels(instance);
+        labels.push((
+            "result",
+            if succeeded { "success" } else { "failure" }.to_string(),
+        ));
+        metrics::counter!("appconfiguration_reconnects_total", &labels).increment(1);
+    }
+
+    /// Records the age of the configuration used to evaluate features and properties.
+    pub(crate) fn record_snapshot_age(environment_id: &str, age: Duration) {
+        metrics::gauge!(
+            "appconfiguration_snapshot_age_seconds",
+            "environment_id" => environment_id.to_string()
+        )
+        .set(age.as_secs_f64());
+    }
+
+    fn labels(instance: &ServiceInstance) -> Vec<(&'static str, String)> {
+        vec![
+            ("environment_id", instance.environment_id().to_string()),
+            ("collection_id", instance.collection_id().to_string()),
+        ]
+    }
+
+    /// Label identifying the kind of an evaluation error.
+    fn error_kind(error: &Error) -> &'static str {
+        match error {
+            Error::EntityEvaluationError(EntityEvaluationError(
+                SegmentEvaluationError::SegmentEvaluationFailed(e),
+            )) => e.source.kind(),
+            Error::EntityEvaluationError(EntityEvaluationError(
+                SegmentEvaluationError::SegmentIdNotFound(_),
+            )) => "segment_id_not_found",
+            Error::InvalidRolloutPercentage { .. } => "invalid_rollout_percentage",
+            Error::DeserializationError(_) => "deserialization_error",
+            Error::MismatchType => "mismatch_type",
+            _ => "other",
+        }
+    }
+}
+
+#[cfg(not(feature = "metrics"))]
+mod recorder {
+    use std::time::{Duration, Instant};
+
+    use crate::client::transport::ServiceInstance;
+    use crate::errors::Result;
+    use crate::evaluation::EvaluationDetails;
+
+    pub(crate) fn record_evaluation(
+        _kind: &'static str,
+        _id: &str,
+        _started: Instant,
+        _result: &Result<EvaluationDetails>,
+    ) {
+    }
+
+    pub(crate) fn record_configuration_fetch(
+        _instance: &ServiceInstance,
+        _started: Instant,
+        _succeeded: bool,
+    ) {
+    }
+
+    pub(crate) fn record_reconnect(_instance: &ServiceInstance, _succeeded: bool) {}
+
+    pub(crate) fn record_snapshot_age(_environment_id: &str, _age: Duration) {}
+}
+
+pub(crate) use recorder::*;
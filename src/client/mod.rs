@@ -13,15 +13,52 @@
 // limitations under the License.
 
 mod app_configuration_client;
+mod app_configuration_evaluated;
 mod app_configuration_ibm_cloud;
+#[cfg(feature = "async")]
+mod app_configuration_ibm_cloud_async;
+mod app_configuration_offline;
 
+pub(crate) mod builder;
 pub(crate) mod cache;
-pub(crate) mod feature_snapshot;
+pub(crate) mod context;
+pub(crate) mod endpoints;
+pub(crate) mod evaluated_bootstrap;
 pub(crate) mod feature_proxy;
+pub(crate) mod feature_snapshot;
 pub(crate) mod http;
-pub(crate) mod property_snapshot;
+#[cfg(feature = "async")]
+pub(crate) mod http_async;
+pub(crate) mod metering;
+pub(crate) mod metrics;
+pub(crate) mod monitor;
+pub(crate) mod options;
+pub(crate) mod persistence;
 pub(crate) mod property_proxy;
-
+pub(crate) mod property_snapshot;
+pub(crate) mod reconnect;
+pub(crate) mod status;
+pub(crate) mod subscriptions;
+pub(crate) mod token;
+pub(crate) mod transport;
 
 pub use app_configuration_client::AppConfigurationClient;
+pub use app_configuration_evaluated::{
+    AppConfigurationEvaluated, EvaluatedFeatureSnapshot, EvaluatedPropertySnapshot,
+};
 pub use app_configuration_ibm_cloud::AppConfigurationClientIBMCloud;
+#[cfg(feature = "async")]
+pub use app_configuration_ibm_cloud_async::AppConfigurationClientIBMCloudAsync;
+pub use app_configuration_offline::AppConfigurationOffline;
+pub use builder::ClientBuilder;
+pub use endpoints::Endpoints;
+pub use evaluated_bootstrap::{EvaluatedBootstrap, EvaluatedFeature, EvaluatedProperty};
+pub use options::ClientOptions;
+pub use reconnect::ReconnectPolicy;
+pub use status::ConnectionState;
+pub use subscriptions::{ChangeCallback, ChangeKind, ConfigurationChange, SubscriptionFilter};
+#[cfg(feature = "async")]
+pub use transport::{AsyncTransport, AsyncUpdateStream, AsyncWebSocketUpdateStream};
+pub use transport::{
+    HttpTransport, ServiceInstance, Transport, UpdateStream, WebSocketUpdateStream,
+};
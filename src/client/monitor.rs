@@ -0,0 +1,306 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use tracing::Span;
+
+use crate::client::cache::ConfigurationSnapshot;
+use crate::client::context::ConnectionContext;
+use crate::client::evaluated_bootstrap::EvaluatedBootstrap;
+use crate::client::feature_snapshot::FeatureSnapshot;
+use crate::client::metering::{Metering, MeteringHandle};
+use crate::client::metrics;
+use crate::client::property_snapshot::PropertySnapshot;
+use crate::client::status::{ConnectionState, ConnectionStatus};
+use crate::client::subscriptions::{
+    update_configuration_snapshot, ChangeCallback, ConfigurationChange, SubscriptionFilter,
+    Subscriptions,
+};
+use crate::client::transport::Transport;
+use crate::errors::{ConfigurationAccessError, Result};
+use crate::evaluation::EvaluatedConfiguration;
+use crate::hooks::Hooks;
+use crate::Entity;
+
+/// Configuration of a client connecting to IBM Cloud, shared with the thread (or task)
+/// keeping it up to date.
+///
+/// The sync and async clients only differ in how they wait for updates, everything
+/// else is implemented here and in [`Monitor`].
+#[derive(Debug)]
+pub(crate) struct ClientState {
+    pub(crate) latest_config_snapshot: Arc<Mutex<ConfigurationSnapshot>>,
+    pub(crate) status: Arc<Mutex<ConnectionStatus>>,
+    pub(crate) subscriptions: Arc<Subscriptions>,
+    pub(crate) metering: Option<MeteringHandle>,
+    pub(crate) hooks: Hooks,
+}
+
+impl ClientState {
+    pub(crate) fn new(config_snapshot: ConfigurationSnapshot) -> Self {
+        Self {
+            latest_config_snapshot: Arc::new(Mutex::new(config_snapshot)),
+            status: Arc::new(Mutex::new(ConnectionStatus::new())),
+            subscriptions: Arc::new(Subscriptions::default()),
+            metering: None,
+            hooks: Hooks::default(),
+        }
+    }
+
+    /// Creates the state of a new client from the result of connecting to the server,
+    /// using the local copies of the configuration if the server is not reachable.
+    ///
+    /// Returns the update stream to monitor, if the connection succeeded.
+    pub(crate) fn start<T: Transport, S>(
+        context: &Arc<ConnectionContext<T>>,
+        initial: Result<(Option<S>, ConfigurationSnapshot)>,
+    ) -> Result<(Self, Option<S>)> {
+        let (stream, config_snapshot, error) = match initial {
+            Ok((stream, config_snapshot)) => (stream, config_snapshot, None),
+            Err(e) => match context.load_local_copy() {
+                Some(config_snapshot) => {
+                    if context.logging {
+                        tracing::warn!(
+                            parent: &context.span,
+                            error = %e,
+                            "Cannot fetch configuration from the server. Using local copy"
+                        );
+                    }
+                    (None, config_snapshot, Some(e))
+                }
+                None => return Err(e),
+            },
+        };
+
+        let mut state = Self::new(config_snapshot);
+        {
+            let mut status = state.status.lock()?;
+            if error.is_none() {
+                status.record_fetch();
+            }
+            status.set_initial(context.live_updates, error.as_ref());
+        }
+        state.metering = context
+            .usage_metering
+            .then(|| MeteringHandle::start(context.clone(), context.metering_interval));
+        state.hooks = context.hooks.clone();
+        Ok((state, stream))
+    }
+
+    /// Returns the [`Monitor`] updating this state.
+    pub(crate) fn monitor<T>(&self, context: Arc<ConnectionContext<T>>) -> Monitor<T> {
+        Monitor {
+            context,
+            latest_config_snapshot: self.latest_config_snapshot.clone(),
+            status: self.status.clone(),
+            subscriptions: self.subscriptions.clone(),
+            attempt: 0,
+        }
+    }
+
+    pub(crate) fn get_connection_state(&self) -> Result<ConnectionState> {
+        Ok(self.status.lock()?.state.clone())
+    }
+
+    pub(crate) fn get_last_successful_fetch(&self) -> Result<Option<SystemTime>> {
+        Ok(self.status.lock()?.last_successful_fetch)
+    }
+
+    pub(crate) fn get_snapshot_age(&self) -> Result<Duration> {
+        Ok(self.latest_config_snapshot.lock()?.age())
+    }
+
+    pub(crate) fn get_feature_ids(&self) -> Result<Vec<String>> {
+        Ok(self
+            .latest_config_snapshot
+            .lock()?
+            .features
+            .keys()
+            .cloned()
+            .collect())
+    }
+
+    pub(crate) fn get_feature(&self, feature_id: &str) -> Result<FeatureSnapshot> {
+        self.latest_config_snapshot
+            .lock()?
+            .get_feature_snapshot(feature_id)
+            .map(|feature| {
+                feature
+                    .with_metering(self.metering.as_ref())
+                    .with_hooks(&self.hooks)
+            })
+    }
+
+    pub(crate) fn get_property_ids(&self) -> Result<Vec<String>> {
+        Ok(self
+            .latest_config_snapshot
+            .lock()
+            .map_err(|_| ConfigurationAccessError::LockAcquisitionError)?
+            .properties
+            .keys()
+            .cloned()
+            .collect())
+    }
+
+    pub(crate) fn get_property(&self, property_id: &str) -> Result<PropertySnapshot> {
+        self.latest_config_snapshot
+            .lock()?
+            .get_property_snapshot(property_id)
+            .map(|property| {
+                property
+                    .with_metering(self.metering.as_ref())
+                    .with_hooks(&self.hooks)
+            })
+    }
+
+    pub(crate) fn evaluate_all(&self, entity: &impl Entity) -> Result<EvaluatedConfiguration> {
+        self.latest_config_snapshot
+            .lock()?
+            .evaluate_all(entity, self.metering())
+    }
+
+    pub(crate) fn evaluate_bootstrap(&self, entity: &impl Entity) -> Result<EvaluatedBootstrap> {
+        self.latest_config_snapshot
+            .lock()?
+            .evaluate_bootstrap(entity, self.metering())
+    }
+
+    fn metering(&self) -> Option<&Metering> {
+        self.metering
+            .as_ref()
+            .map(|handle| handle.metering.as_ref())
+    }
+
+    pub(crate) fn subscribe(
+        &self,
+        filter: SubscriptionFilter,
+    ) -> Result<Receiver<ConfigurationChange>> {
+        self.subscriptions.subscribe(filter)
+    }
+
+    pub(crate) fn subscribe_with_callback(
+        &self,
+        filter: SubscriptionFilter,
+        callback: ChangeCallback,
+    ) -> Result<()> {
+        self.subscriptions.subscribe_with_callback(filter, callback)
+    }
+}
+
+/// Steps of the thread (or task) monitoring the configuration of a client, common to
+/// the sync and async clients.
+///
+/// The loop waiting for updates calls [`Monitor::tick`] on every iteration,
+/// [`Monitor::reconnected`] after every reconnection attempt and [`Monitor::updated`]
+/// after every update notification.
+pub(crate) struct Monitor<T> {
+    pub(crate) context: Arc<ConnectionContext<T>>,
+    latest_config_snapshot: Arc<Mutex<ConfigurationSnapshot>>,
+    status: Arc<Mutex<ConnectionStatus>>,
+    subscriptions: Arc<Subscriptions>,
+    /// Failed reconnection attempts since the connection was lost.
+    attempt: u32,
+}
+
+impl<T> Monitor<T> {
+    /// Refreshes the snapshot age gauge, so it keeps growing while nothing is evaluated.
+    pub(crate) fn tick(&self) -> Result<()> {
+        self.latest_config_snapshot.lock()?.record_age();
+        Ok(())
+    }
+
+    /// Returns how long to wait before the next reconnection attempt.
+    pub(crate) fn next_reconnect_delay(&mut self) -> Duration {
+        let delay = self.context.reconnect_policy.delay(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+        delay
+    }
+
+    /// Span the reconnection attempts run in.
+    pub(crate) fn reconnect_span(&self) -> Span {
+        tracing::info_span!(parent: &self.context.span, "reconnect", attempt = self.attempt)
+    }
+
+    /// Handles the result of a reconnection attempt. Returns the new update stream
+    /// if it succeeded.
+    pub(crate) fn reconnected<S>(
+        &mut self,
+        result: Result<(S, ConfigurationSnapshot)>,
+    ) -> Result<Option<S>> {
+        metrics::record_reconnect(&self.context.instance, result.is_ok());
+        match result {
+            Ok((stream, config_snapshot)) => {
+                self.replace_snapshot(config_snapshot)?;
+                self.status.lock()?.set_live();
+                self.attempt = 0;
+                Ok(Some(stream))
+            }
+            Err(e) => {
+                if self.context.logging {
+                    tracing::warn!(
+                        parent: &self.context.span,
+                        attempt = self.attempt,
+                        error = %e,
+                        "Reconnection attempt failed"
+                    );
+                }
+                self.status.lock()?.set_reconnecting(self.attempt, &e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Handles the configuration fetched after the server notified an update. Returns
+    /// `false` if the update stream is broken and the client has to reconnect.
+    pub(crate) fn updated(&self, result: Result<ConfigurationSnapshot>) -> Result<bool> {
+        match result {
+            Ok(config_snapshot) => {
+                self.replace_snapshot(config_snapshot)?;
+                Ok(true)
+            }
+            Err(e) => {
+                if self.context.logging {
+                    tracing::warn!(
+                        parent: &self.context.span,
+                        error = %e,
+                        "Waiting for configuration update failed. Reconnecting"
+                    );
+                }
+                self.status.lock()?.set_reconnecting(self.attempt, &e);
+                Ok(false)
+            }
+        }
+    }
+
+    /// Records that the monitoring finished: the client is gone or the snapshot cannot
+    /// be updated anymore.
+    pub(crate) fn stopped(&self, result: Result<()>) {
+        if let Ok(mut status) = self.status.lock() {
+            status.set_stopped(result.as_ref().err());
+        }
+    }
+
+    fn replace_snapshot(&self, config_snapshot: ConfigurationSnapshot) -> Result<()> {
+        update_configuration_snapshot(
+            &self.latest_config_snapshot,
+            &self.subscriptions,
+            config_snapshot,
+        )?;
+        self.status.lock()?.record_fetch();
+        self.tick()
+    }
+}
@@ -0,0 +1,182 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use url::Url;
+
+use crate::client::endpoints::Endpoints;
+use crate::client::http::WebSocketProxy;
+use crate::client::reconnect::ReconnectPolicy;
+use crate::errors::BuilderError;
+use crate::EvaluationHook;
+
+/// Additional options to configure the clients connecting to IBM Cloud.
+///
+/// The options are validated when the client is created, returning a [`BuilderError`]
+/// describing the first invalid one. [`ClientBuilder`](crate::ClientBuilder) sets them
+/// one by one.
+#[derive(Debug, Clone)]
+pub struct ClientOptions {
+    /// How to reconnect when the connection to receive live-updates is lost.
+    pub reconnect_policy: ReconnectPolicy,
+
+    /// File where every configuration fetched from the server is stored.
+    ///
+    /// If the server cannot be reached when the client is created, the configuration
+    /// stored in this file is used until the connection is established.
+    pub cache_file: Option<PathBuf>,
+
+    /// File with an exported configuration to use when the server cannot be reached
+    /// at startup and there is no usable `cache_file`.
+    pub bootstrap_file: Option<PathBuf>,
+
+    /// Timeout for each HTTP request sent to the server.
+    pub timeout: Option<Duration>,
+
+    /// Timeout for the connect phase of each HTTP request.
+    pub connect_timeout: Option<Duration>,
+
+    /// URL of the proxy used for the HTTP requests. The websocket receiving live-updates
+    /// is tunneled through it with a `CONNECT` request, so it must be an `http` proxy.
+    pub proxy: Option<String>,
+
+    /// URLs of the services the client connects to.
+    pub endpoints: Endpoints,
+
+    /// Whether to keep a connection open to receive live-updates. If disabled, the
+    /// configuration is fetched only once, when the client is created.
+    pub live_updates: bool,
+
+    /// Whether to emit [`tracing`](https://docs.rs/tracing) warnings about connection problems.
+    pub logging: bool,
+
+    /// Whether to send to the server how many times each feature and property is
+    /// evaluated, for each entity and segment, so the usage is shown in the dashboard.
+    ///
+    /// The bulk evaluations, like [`evaluate_all`](crate::AppConfigurationClient::evaluate_all),
+    /// record every feature and property evaluated.
+    pub usage_metering: bool,
+
+    /// How often the usage is sent to the server when `usage_metering` is enabled.
+    pub metering_interval: Duration,
+
+    /// Hooks called around the evaluation of the features and properties, see [`EvaluationHook`].
+    pub hooks: Vec<Arc<dyn EvaluationHook>>,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            reconnect_policy: ReconnectPolicy::default(),
+            cache_file: None,
+            bootstrap_file: None,
+            timeout: None,
+            connect_timeout: None,
+            proxy: None,
+            endpoints: Endpoints::default(),
+            live_updates: true,
+            logging: true,
+            usage_metering: false,
+            metering_interval: Duration::from_secs(600),
+            hooks: Vec::new(),
+        }
+    }
+}
+
+impl ClientOptions {
+    /// Checks the options, both [`ClientBuilder`](crate::ClientBuilder) and the client
+    /// constructors call it.
+    pub(crate) fn validate(&self) -> std::result::Result<(), BuilderError> {
+        let endpoints = &self.endpoints;
+        validate_url("service_url", &endpoints.service_url, &["http", "https"])?;
+        validate_url("websocket_url", &endpoints.websocket_url, &["ws", "wss"])?;
+        validate_url("iam_url", &endpoints.iam_url, &["http", "https"])?;
+        if let Some(proxy) = &self.proxy {
+            reqwest::Proxy::all(proxy).map_err(|e| BuilderError::InvalidUrl {
+                setting: "proxy",
+                url: proxy.clone(),
+                reason: e.to_string(),
+            })?;
+            if self.live_updates {
+                WebSocketProxy::parse(proxy).map_err(|e| BuilderError::InvalidUrl {
+                    setting: "proxy",
+                    url: proxy.clone(),
+                    reason: e.to_string(),
+                })?;
+            }
+        }
+
+        validate_duration("timeout", self.timeout)?;
+        validate_duration("connect_timeout", self.connect_timeout)?;
+        validate_duration("metering_interval", Some(self.metering_interval))?;
+        self.reconnect_policy.validate()?;
+
+        if let Some(cache_file) = &self.cache_file {
+            let directory = match cache_file.parent() {
+                Some(parent) if parent != Path::new("") => parent,
+                _ => Path::new("."),
+            };
+            if !directory.is_dir() {
+                return Err(BuilderError::InvalidPath {
+                    setting: "cache_file",
+                    path: cache_file.clone(),
+                    reason: "the parent directory does not exist".to_string(),
+                });
+            }
+        }
+        if let Some(bootstrap_file) = &self.bootstrap_file {
+            if !bootstrap_file.is_file() {
+                return Err(BuilderError::InvalidPath {
+                    setting: "bootstrap_file",
+                    path: bootstrap_file.clone(),
+                    reason: "the file does not exist".to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_url(
+    setting: &'static str,
+    url: &Option<String>,
+    schemes: &[&str],
+) -> std::result::Result<(), BuilderError> {
+    let Some(url) = url else {
+        return Ok(());
+    };
+    let invalid = |reason: String| BuilderError::InvalidUrl {
+        setting,
+        url: url.clone(),
+        reason,
+    };
+    let parsed = Url::parse(url).map_err(|e| invalid(e.to_string()))?;
+    if !schemes.contains(&parsed.scheme()) {
+        return Err(invalid(format!("expected scheme {}", schemes.join(" or "))));
+    }
+    Ok(())
+}
+
+fn validate_duration(
+    setting: &'static str,
+    duration: Option<Duration>,
+) -> std::result::Result<(), BuilderError> {
+    match duration {
+        Some(duration) if duration.is_zero() => Err(BuilderError::ZeroDuration(setting)),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,164 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::client::cache::ConfigurationSnapshot;
+use crate::errors::{Error, Result};
+use crate::models::Configuration;
+
+/// Local copies of the configuration used when the server cannot be reached.
+///
+/// Every configuration fetched from the server is written to the `cache_file`. When
+/// the client starts and the server is not reachable, the configuration is read
+/// from the `cache_file` or, if it is not available, from the `bootstrap_file`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Persistence {
+    cache_file: Option<PathBuf>,
+    bootstrap_file: Option<PathBuf>,
+}
+
+impl Persistence {
+    pub fn new(cache_file: Option<PathBuf>, bootstrap_file: Option<PathBuf>) -> Self {
+        Self {
+            cache_file,
+            bootstrap_file,
+        }
+    }
+
+    /// Writes the configuration to the cache file (if any).
+    ///
+    /// The content is written to a temporary file first and then renamed, so a
+    /// reader never finds a partially written cache file.
+    pub fn save(&self, configuration: &Configuration) -> Result<()> {
+        let Some(cache_file) = &self.cache_file else {
+            return Ok(());
+        };
+        let mut tmp_file = cache_file.clone().into_os_string();
+        tmp_file.push(".tmp");
+
+        let mut writer = BufWriter::new(File::create(&tmp_file)?);
+        serde_json::to_writer(&mut writer, configuration)
+            .map_err(|e| Error::Other(format!("Cannot serialize configuration: {e}")))?;
+        writer.flush()?;
+        drop(writer);
+
+        fs::rename(&tmp_file, cache_file)?;
+        Ok(())
+    }
+
+    /// Returns the configuration stored in the cache file or, if it cannot be
+    /// used, the one in the bootstrap file. `on_error` is called for every file
+    /// that cannot be used, except for a cache file that doesn't exist yet.
+    ///
+    /// The snapshot is dated with the modification time of the file, so its age
+    /// reflects how old the stored configuration is.
+    pub fn load(
+        &self,
+        environment_id: &str,
+        mut on_error: impl FnMut(&Path, Error),
+    ) -> Option<ConfigurationSnapshot> {
+        [&self.cache_file, &self.bootstrap_file]
+            .into_iter()
+            .flatten()
+            .find_map(|path| match Self::load_file(path, environment_id) {
+                Ok(snapshot) => Some(snapshot),
+                // No configuration has been fetched yet, not an error
+                Err(Error::IoError(e))
+                    if e.kind() == io::ErrorKind::NotFound
+                        && self.cache_file.as_ref() == Some(path) =>
+                {
+                    None
+                }
+                Err(e) => {
+                    on_error(path, e);
+                    None
+                }
+            })
+    }
+
+    fn load_file(path: &Path, environment_id: &str) -> Result<ConfigurationSnapshot> {
+        let file = File::open(path)?;
+        let modified = file.metadata()?.modified()?;
+        let configuration: Configuration = serde_json::from_reader(BufReader::new(file))
+            .map_err(Error::ConfigurationParseError)?;
+        let mut snapshot = ConfigurationSnapshot::new(environment_id, configuration)?;
+        snapshot.created_at = modified;
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tests::{configuration_feature1_enabled, example_configuration_enterprise};
+    use rstest::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("appconfiguration-{}-{name}", std::process::id()));
+        path
+    }
+
+    #[rstest]
+    fn test_save_and_load(configuration_feature1_enabled: Configuration) {
+        let cache_file = temp_path("cache-save-and-load.json");
+        let persistence = Persistence::new(Some(cache_file.clone()), None);
+        // A missing cache file is not reported as an error
+        let mut errors = Vec::new();
+        assert!(persistence
+            .load("environment_id", |path, _| errors.push(path.to_path_buf()))
+            .is_none());
+        assert!(errors.is_empty());
+
+        persistence.save(&configuration_feature1_enabled).unwrap();
+        let snapshot = persistence.load("environment_id", |_, _| {}).unwrap();
+        assert_eq!(
+            snapshot.get_feature("f1").unwrap(),
+            &configuration_feature1_enabled.environments[0].features[0]
+        );
+
+        fs::remove_file(cache_file).unwrap();
+    }
+
+    #[rstest]
+    fn test_load_falls_back_to_bootstrap(example_configuration_enterprise: Configuration) {
+        let cache_file = temp_path("cache-fallback.json");
+        fs::write(&cache_file, "not a configuration").unwrap();
+        let bootstrap_file = temp_path("bootstrap-fallback.json");
+        Persistence::new(Some(bootstrap_file.clone()), None)
+            .save(&example_configuration_enterprise)
+            .unwrap();
+
+        let persistence = Persistence::new(Some(cache_file.clone()), Some(bootstrap_file.clone()));
+        let mut errors = Vec::new();
+        let snapshot = persistence
+            .load("dev", |path, _| errors.push(path.to_path_buf()))
+            .unwrap();
+        assert_eq!(snapshot.features.len(), 6);
+        assert_eq!(errors, vec![cache_file.clone()]);
+
+        // Environment not available in any of the files
+        let mut errors = Vec::new();
+        assert!(persistence
+            .load("production", |path, _| errors.push(path.to_path_buf()))
+            .is_none());
+        assert_eq!(errors, vec![cache_file.clone(), bootstrap_file.clone()]);
+
+        fs::remove_file(cache_file).unwrap();
+        fs::remove_file(bootstrap_file).unwrap();
+    }
+}
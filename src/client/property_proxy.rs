@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use serde::de::DeserializeOwned;
+
 use crate::Property;
 
 use super::property_snapshot::PropertySnapshot;
@@ -58,4 +60,10 @@ impl<'a> Property for PropertyProxy<'a> {
             .get_property(&self.property_id)?
             .get_value_into(entity)
     }
+
+    fn get_value_as<T: DeserializeOwned>(&self, entity: &impl Entity) -> crate::errors::Result<T> {
+        self.client
+            .get_property(&self.property_id)?
+            .get_value_as(entity)
+    }
 }
@@ -12,10 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use serde::de::DeserializeOwned;
+
 use crate::entity::Entity;
+use crate::evaluation::{EvaluationDetails, EvaluationReason};
 use crate::value::Value;
 use crate::Property;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::metering::{Metering, MeteringHandle};
+use super::metrics;
+use crate::hooks::{Hooks, ResourceKind};
 
 use crate::errors::Result;
 use crate::segment_evaluation::find_applicable_segment_rule_for_entity;
@@ -25,42 +34,115 @@ use crate::segment_evaluation::find_applicable_segment_rule_for_entity;
 pub struct PropertySnapshot {
     property: crate::models::Property,
     segments: HashMap<String, crate::models::Segment>,
+    metering: Option<Arc<Metering>>,
+    hooks: Hooks,
 }
 
 impl PropertySnapshot {
     pub(crate) fn new(
         property: crate::models::Property,
         segments: HashMap<String, crate::models::Segment>,
+    ) -> Self {
+        Self {
+            property,
+            segments,
+            metering: None,
+            hooks: Hooks::default(),
+        }
+    }
+
+    /// Records the evaluations of this snapshot in the client usage, if enabled.
+    pub(crate) fn with_metering(mut self, metering: Option<&MeteringHandle>) -> Self {
+        self.metering = metering.map(|handle| handle.metering.clone());
+        self
+    }
+
+    /// Calls the client hooks around the evaluations of this snapshot.
+    pub(crate) fn with_hooks(mut self, hooks: &Hooks) -> Self {
+        self.hooks = hooks.clone();
+        self
+    }
+
+    /// Evaluates the property for the given [`Entity`] and returns the resulting value
+    /// together with the reason and the matched targeting rule and segment.
+    pub fn evaluate_details(&self, entity: &impl Entity) -> Result<EvaluationDetails> {
+        let details = self.hooks.run(
+            ResourceKind::Property,
+            &self.property.property_id,
+            entity,
+            || PropertyEvaluation::new(&self.property, &self.segments).evaluate_details(entity),
+        )?;
+        if let Some(metering) = &self.metering {
+            metering.record_property(
+                &self.property.property_id,
+                entity.get_id(),
+                details.segment_id.as_deref(),
+            );
+        }
+        Ok(details)
+    }
+}
+
+/// Evaluates a property borrowing its data, so it can be used directly on a
+/// [`ConfigurationSnapshot`](super::cache::ConfigurationSnapshot).
+pub(crate) struct PropertyEvaluation<'a> {
+    property: &'a crate::models::Property,
+    segments: &'a HashMap<String, crate::models::Segment>,
+}
+
+impl<'a> PropertyEvaluation<'a> {
+    pub(crate) fn new(
+        property: &'a crate::models::Property,
+        segments: &'a HashMap<String, crate::models::Segment>,
     ) -> Self {
         Self { property, segments }
     }
 
-    fn evaluate_feature_for_entity(
-        &self,
-        entity: &impl Entity,
-    ) -> Result<crate::models::ConfigValue> {
+    pub(crate) fn evaluate_details(&self, entity: &impl Entity) -> Result<EvaluationDetails> {
+        let _span =
+            tracing::trace_span!("evaluate_property", property_id = %self.property.property_id)
+                .entered();
+        let started = Instant::now();
+        let result = self.evaluate(entity);
+        metrics::record_evaluation("property", &self.property.property_id, started, &result);
+        result
+    }
+
+    fn evaluate(&self, entity: &impl Entity) -> Result<EvaluationDetails> {
         if self.property.segment_rules.is_empty() || entity.get_attributes().is_empty() {
             // TODO: this makes only sense if there can be a rule which matches
             //       even on empty attributes
             // No match possible. Do not consider segment rules:
-            return Ok(self.property.value.clone());
+            let value = self.to_value(self.property.value.clone())?;
+            return Ok(EvaluationDetails::new(value, EvaluationReason::Default));
         }
 
         match find_applicable_segment_rule_for_entity(
-            &self.segments,
+            self.segments,
             self.property.segment_rules.clone().into_iter(),
             entity,
         )? {
-            Some(segment_rule) => {
-                if segment_rule.value.is_default() {
-                    Ok(self.property.value.clone())
+            Some((segment_rule, segment_id)) => {
+                let value = if segment_rule.value.is_default() {
+                    self.property.value.clone()
                 } else {
-                    Ok(segment_rule.value)
-                }
+                    segment_rule.value
+                };
+                Ok(
+                    EvaluationDetails::new(self.to_value(value)?, EvaluationReason::TargetingMatch)
+                        .with_targeting_rule(segment_rule.order, segment_id),
+                )
+            }
+            None => {
+                let value = self.to_value(self.property.value.clone())?;
+                Ok(EvaluationDetails::new(value, EvaluationReason::Default))
             }
-            None => Ok(self.property.value.clone()),
         }
     }
+
+    fn to_value(&self, value: crate::models::ConfigValue) -> Result<Value> {
+        (self.property.kind, self.property.format, value).try_into()
+    }
 }
 
 impl Property for PropertySnapshot {
@@ -69,8 +151,7 @@ impl Property for PropertySnapshot {
     }
 
     fn get_value(&self, entity: &impl Entity) -> Result<Value> {
-        let model_value = self.evaluate_feature_for_entity(entity)?;
-        (self.property.kind, model_value).try_into()
+        Ok(self.evaluate_details(entity)?.value)
     }
 
     fn get_value_into<T: TryFrom<Value, Error = crate::Error>>(
@@ -80,6 +161,10 @@ impl Property for PropertySnapshot {
         let value = self.get_value(entity)?;
         value.try_into()
     }
+
+    fn get_value_as<T: DeserializeOwned>(&self, entity: &impl Entity) -> Result<T> {
+        self.get_value(entity)?.deserialize_into()
+    }
 }
 
 #[cfg(test)]
@@ -93,7 +178,7 @@ pub mod tests {
             name: "F1".to_string(),
             property_id: "f1".to_string(),
             kind: ValueKind::Numeric,
-            _format: None,
+            format: None,
             value: ConfigValue(serde_json::Value::Number((-42).into())),
             segment_rules: vec![TargetingRule {
                 rules: vec![Segments {
@@ -118,6 +203,7 @@ pub mod tests {
                         attribute_name: "name".into(),
                         operator: "is".into(),
                         values: vec!["heinz".into()],
+                        compiled_values: None,
                     }],
                 },
             )]),
@@ -138,7 +224,7 @@ pub mod tests {
             name: "F1".to_string(),
             property_id: "f1".to_string(),
             kind: ValueKind::Numeric,
-            _format: None,
+            format: None,
             value: ConfigValue(serde_json::Value::Number((-42).into())),
             segment_rules: vec![
                 TargetingRule {
@@ -174,6 +260,7 @@ pub mod tests {
                             attribute_name: "name".into(),
                             operator: "is".into(),
                             values: vec!["heinz".into()],
+                            compiled_values: None,
                         }],
                     },
                 ),
@@ -188,6 +275,7 @@ pub mod tests {
                             attribute_name: "name".into(),
                             operator: "is".into(),
                             values: vec!["heinz".into()],
+                            compiled_values: None,
                         }],
                     },
                 ),
@@ -202,4 +290,63 @@ pub mod tests {
         let value = property.get_value(&entity).unwrap();
         assert!(matches!(value, Value::Int64(ref v) if v == &(-49)));
     }
+
+    #[test]
+    fn test_evaluate_details() {
+        let inner_property = crate::models::Property {
+            name: "F1".to_string(),
+            property_id: "f1".to_string(),
+            kind: ValueKind::Numeric,
+            format: None,
+            value: ConfigValue(serde_json::Value::Number((-42).into())),
+            segment_rules: vec![TargetingRule {
+                rules: vec![Segments {
+                    segments: vec!["some_segment_id_1".into()],
+                }],
+                value: ConfigValue(serde_json::Value::Number((-48).into())),
+                order: 1,
+                rollout_percentage: Some(ConfigValue(serde_json::Value::Number((100).into()))),
+            }],
+            _tags: None,
+        };
+        let property = PropertySnapshot::new(
+            inner_property,
+            HashMap::from([(
+                "some_segment_id_1".into(),
+                Segment {
+                    _name: "".into(),
+                    segment_id: "".into(),
+                    _description: "".into(),
+                    _tags: None,
+                    rules: vec![SegmentRule {
+                        attribute_name: "name".into(),
+                        operator: "is".into(),
+                        values: vec!["heinz".into()],
+                        compiled_values: None,
+                    }],
+                },
+            )]),
+        );
+
+        let entity = crate::tests::GenericEntity {
+            id: "a2".into(),
+            attributes: HashMap::from([("name".into(), Value::from("heinz".to_string()))]),
+        };
+        let details = property.evaluate_details(&entity).unwrap();
+        assert_eq!(details.value, Value::Int64(-48));
+        assert_eq!(details.reason, EvaluationReason::TargetingMatch);
+        assert_eq!(details.rule_order, Some(1));
+        assert_eq!(details.segment_id.as_deref(), Some("some_segment_id_1"));
+        assert_eq!(details.rollout_percentage, None);
+
+        let entity = crate::tests::GenericEntity {
+            id: "a2".into(),
+            attributes: HashMap::from([("name".into(), Value::from("heinzz".to_string()))]),
+        };
+        let details = property.evaluate_details(&entity).unwrap();
+        assert_eq!(
+            details,
+            EvaluationDetails::new(Value::Int64(-42), EvaluationReason::Default)
+        );
+    }
 }
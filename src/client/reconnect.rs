@@ -0,0 +1,180 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use crate::errors::BuilderError;
+
+/// Controls how a client reconnects to the server after losing the connection
+/// used to receive live-updates.
+///
+/// The delay before each attempt grows exponentially (`initial_delay * multiplier^attempt`)
+/// up to `max_delay`. A random `jitter` (as a fraction of the delay) is applied to every
+/// delay so many clients don't reconnect at the same time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Delay before the first reconnection attempt.
+    pub initial_delay: Duration,
+    /// Upper bound for the delay between attempts.
+    pub max_delay: Duration,
+    /// Factor applied to the delay after each failed attempt.
+    pub multiplier: f64,
+    /// Fraction of the delay used as random jitter, between `0.0` and `1.0`.
+    pub jitter: f64,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Checks that the delays can be computed as documented.
+    pub(crate) fn validate(&self) -> Result<(), BuilderError> {
+        let invalid = |setting, reason: &str| BuilderError::InvalidSetting {
+            setting,
+            reason: reason.to_string(),
+        };
+        if !(self.multiplier.is_finite() && self.multiplier >= 1.0) {
+            return Err(invalid(
+                "reconnect_policy.multiplier",
+                "must be a finite number greater than or equal to 1.0",
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err(invalid(
+                "reconnect_policy.jitter",
+                "must be a number between 0.0 and 1.0",
+            ));
+        }
+        if self.initial_delay > self.max_delay {
+            return Err(invalid(
+                "reconnect_policy.initial_delay",
+                "cannot be greater than max_delay",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the delay to wait before the given attempt (starting at `0`).
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        self.delay_with_random(attempt, random_fraction())
+    }
+
+    /// Returns the delay for the given attempt, `random` is a value in `[0.0, 1.0)`.
+    ///
+    /// Invalid settings never panic: a NaN `multiplier` or `jitter` is ignored and a
+    /// delay that cannot be represented falls back to `max_delay`.
+    fn delay_with_random(&self, attempt: u32, random: f64) -> Duration {
+        // `f64::max` returns `1.0` if the multiplier is NaN
+        let multiplier = self.multiplier.max(1.0);
+        let exponential =
+            self.initial_delay.as_secs_f64() * multiplier.powi(attempt.min(i32::MAX as u32) as i32);
+        // `f64::min` returns `max_delay` if `exponential` is NaN (zero times infinity)
+        let delay = exponential.min(self.max_delay.as_secs_f64());
+        let jitter = match self.jitter.is_nan() {
+            true => 0.0,
+            false => self.jitter.clamp(0.0, 1.0),
+        };
+        Duration::try_from_secs_f64(delay * (1.0 - jitter + 2.0 * jitter * random))
+            .unwrap_or(self.max_delay)
+    }
+}
+
+/// Returns a pseudo-random value in `[0.0, 1.0)`. Good enough to spread reconnections.
+fn random_fraction() -> f64 {
+    let value = RandomState::new().build_hasher().finish();
+    (value >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_grows_exponentially() {
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.0,
+        };
+        assert_eq!(policy.delay(0), Duration::from_secs(1));
+        assert_eq!(policy.delay(1), Duration::from_secs(2));
+        assert_eq!(policy.delay(2), Duration::from_secs(4));
+        assert_eq!(policy.delay(3), Duration::from_secs(8));
+        // Bounded by max_delay
+        assert_eq!(policy.delay(4), Duration::from_secs(10));
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_delay_jitter() {
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_secs(10),
+            max_delay: Duration::from_secs(60),
+            multiplier: 2.0,
+            jitter: 0.5,
+        };
+        assert_eq!(policy.delay_with_random(0, 0.0), Duration::from_secs(5));
+        assert_eq!(policy.delay_with_random(0, 0.5), Duration::from_secs(10));
+        assert_eq!(policy.delay_with_random(1, 0.75), Duration::from_secs(25));
+
+        for _ in 0..100 {
+            let delay = policy.delay(0);
+            assert!(delay >= Duration::from_secs(5) && delay <= Duration::from_secs(15));
+        }
+    }
+
+    #[test]
+    fn test_delay_with_invalid_settings() {
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+            multiplier: f64::NAN,
+            jitter: f64::NAN,
+        };
+        assert_eq!(policy.delay_with_random(0, 0.9), Duration::from_secs(1));
+        assert_eq!(policy.delay_with_random(5, 0.9), Duration::from_secs(1));
+
+        // Zero times infinity is NaN
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::ZERO,
+            max_delay: Duration::from_secs(10),
+            multiplier: f64::INFINITY,
+            jitter: 0.0,
+        };
+        assert_eq!(policy.delay(3), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_delay_overflow() {
+        let policy = ReconnectPolicy {
+            initial_delay: Duration::MAX,
+            max_delay: Duration::MAX,
+            multiplier: f64::MAX,
+            jitter: 1.0,
+        };
+        assert_eq!(policy.delay_with_random(u32::MAX, 0.99), Duration::MAX);
+        assert_eq!(policy.delay_with_random(0, 0.0), Duration::ZERO);
+    }
+}
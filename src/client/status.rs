@@ -0,0 +1,124 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::SystemTime;
+
+use crate::errors::Error;
+
+/// State of the connection a client uses to receive live-updates from the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// The client is connected and receiving live-updates.
+    Live,
+    /// The connection was lost and the client is trying to reconnect. In the
+    /// meantime, the last configuration received is used.
+    Reconnecting {
+        /// Number of failed reconnection attempts so far.
+        attempt: u32,
+        /// Description of the last error.
+        last_error: String,
+    },
+    /// The client no longer receives live-updates. The last configuration received
+    /// is used forever.
+    Stopped {
+        /// Description of the error that stopped the monitoring, if any.
+        last_error: Option<String>,
+    },
+}
+
+/// Tracks the connection state and the last successful configuration fetch.
+#[derive(Debug)]
+pub(crate) struct ConnectionStatus {
+    pub(crate) state: ConnectionState,
+    pub(crate) last_successful_fetch: Option<SystemTime>,
+}
+
+impl ConnectionStatus {
+    /// Status of a client not monitoring the configuration, until
+    /// [`ConnectionStatus::set_initial`] sets the actual state.
+    pub(crate) fn new() -> Self {
+        Self {
+            state: ConnectionState::Stopped { last_error: None },
+            last_successful_fetch: None,
+        }
+    }
+
+    /// Records that the configuration was fetched from the server right now.
+    pub(crate) fn record_fetch(&mut self) {
+        self.last_successful_fetch = Some(SystemTime::now());
+    }
+
+    pub(crate) fn set_live(&mut self) {
+        self.state = ConnectionState::Live;
+    }
+
+    pub(crate) fn set_reconnecting(&mut self, attempt: u32, error: &Error) {
+        self.state = ConnectionState::Reconnecting {
+            attempt,
+            last_error: error.to_string(),
+        };
+    }
+
+    pub(crate) fn set_stopped(&mut self, error: Option<&Error>) {
+        self.state = ConnectionState::Stopped {
+            last_error: error.map(|e| e.to_string()),
+        };
+    }
+
+    /// Sets the state right after creating a client. `error` is the one that prevented
+    /// fetching the configuration from the server, if any.
+    pub(crate) fn set_initial(&mut self, live_updates: bool, error: Option<&Error>) {
+        match (live_updates, error) {
+            (false, error) => self.set_stopped(error),
+            (true, None) => self.set_live(),
+            (true, Some(error)) => self.set_reconnecting(0, error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_status_transitions() {
+        let mut status = ConnectionStatus::new();
+        assert_eq!(status.state, ConnectionState::Stopped { last_error: None });
+        assert!(status.last_successful_fetch.is_none());
+
+        status.record_fetch();
+        status.set_live();
+        assert_eq!(status.state, ConnectionState::Live);
+        let fetched_at = status.last_successful_fetch.unwrap();
+
+        status.set_reconnecting(2, &Error::Other("Connection closed by the server".into()));
+        assert_eq!(
+            status.state,
+            ConnectionState::Reconnecting {
+                attempt: 2,
+                last_error: "Connection closed by the server".into()
+            }
+        );
+        // The last fetch is kept while reconnecting
+        assert_eq!(status.last_successful_fetch, Some(fetched_at));
+
+        status.set_stopped(Some(&Error::CannotAcquireLock));
+        assert_eq!(
+            status.state,
+            ConnectionState::Stopped {
+                last_error: Some("Cannot acquire snapshot lock".into())
+            }
+        );
+    }
+}
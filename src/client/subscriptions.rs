@@ -0,0 +1,484 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+use crate::client::cache::ConfigurationSnapshot;
+use crate::errors::Result;
+use crate::models::{Segment, TargetingRule};
+
+/// Describes how a feature or a property changed between two configurations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The resource was not available in the previous configuration.
+    Added,
+    /// The resource is no longer available.
+    Removed,
+    /// The resource is available in both configurations, but it was modified.
+    Modified {
+        /// Name, type, format, values, enabled state or rollout percentage changed.
+        value_changed: bool,
+        /// Targeting rules, or the segments they reference, changed.
+        rules_changed: bool,
+    },
+}
+
+/// Changes between two consecutive configurations received by a client.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigurationChange {
+    /// Changed features, by feature id.
+    pub features: HashMap<String, ChangeKind>,
+    /// Changed properties, by property id.
+    pub properties: HashMap<String, ChangeKind>,
+}
+
+impl ConfigurationChange {
+    pub(crate) fn between(old: &ConfigurationSnapshot, new: &ConfigurationSnapshot) -> Self {
+        let features = diff(
+            &old.features,
+            &new.features,
+            |a, b| {
+                a.name == b.name
+                    && a.kind == b.kind
+                    && a.format == b.format
+                    && a.enabled_value == b.enabled_value
+                    && a.disabled_value == b.disabled_value
+                    && a.enabled == b.enabled
+                    && a.rollout_percentage == b.rollout_percentage
+            },
+            |f| &f.segment_rules,
+            old,
+            new,
+        );
+        let properties = diff(
+            &old.properties,
+            &new.properties,
+            |a, b| {
+                a.name == b.name && a.kind == b.kind && a.format == b.format && a.value == b.value
+            },
+            |p| &p.segment_rules,
+            old,
+            new,
+        );
+        Self {
+            features,
+            properties,
+        }
+    }
+
+    /// Returns `true` if no feature nor property changed.
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty() && self.properties.is_empty()
+    }
+
+    fn filtered(&self, filter: &SubscriptionFilter) -> Self {
+        Self {
+            features: filter_ids(&self.features, filter.features.as_ref(), filter),
+            properties: filter_ids(&self.properties, filter.properties.as_ref(), filter),
+        }
+    }
+}
+
+fn diff<T>(
+    old: &HashMap<String, T>,
+    new: &HashMap<String, T>,
+    same_values: impl Fn(&T, &T) -> bool,
+    rules: impl Fn(&T) -> &Vec<TargetingRule>,
+    old_snapshot: &ConfigurationSnapshot,
+    new_snapshot: &ConfigurationSnapshot,
+) -> HashMap<String, ChangeKind> {
+    let mut changes = HashMap::new();
+    for (id, new_item) in new.iter() {
+        match old.get(id) {
+            None => {
+                changes.insert(id.clone(), ChangeKind::Added);
+            }
+            Some(old_item) => {
+                let value_changed = !same_values(old_item, new_item);
+                let rules_changed = rules(old_item) != rules(new_item)
+                    || referenced_segments(rules(old_item), &old_snapshot.segments)
+                        != referenced_segments(rules(new_item), &new_snapshot.segments);
+                if value_changed || rules_changed {
+                    changes.insert(
+                        id.clone(),
+                        ChangeKind::Modified {
+                            value_changed,
+                            rules_changed,
+                        },
+                    );
+                }
+            }
+        }
+    }
+    for id in old.keys().filter(|id| !new.contains_key(*id)) {
+        changes.insert(id.clone(), ChangeKind::Removed);
+    }
+    changes
+}
+
+fn referenced_segments<'a>(
+    rules: &[TargetingRule],
+    segments: &'a HashMap<String, Segment>,
+) -> HashMap<&'a str, &'a Segment> {
+    rules
+        .iter()
+        .flat_map(|targeting_rule| targeting_rule.rules.iter().flat_map(|s| &s.segments))
+        .filter_map(|segment_id| segments.get_key_value(segment_id))
+        .map(|(k, v)| (k.as_str(), v))
+        .collect()
+}
+
+fn filter_ids(
+    changes: &HashMap<String, ChangeKind>,
+    ids: Option<&HashSet<String>>,
+    filter: &SubscriptionFilter,
+) -> HashMap<String, ChangeKind> {
+    changes
+        .iter()
+        .filter(|(id, _)| match ids {
+            Some(ids) => ids.contains(*id),
+            // If the filter lists some ids, resources of the other type are not included
+            None => filter.features.is_none() && filter.properties.is_none(),
+        })
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect()
+}
+
+/// Selects the features and properties a subscription is interested in.
+///
+/// The default filter matches every feature and property. Once a feature or a
+/// property is added to the filter, only the listed ids will be notified.
+///
+/// # Examples
+///
+/// ```
+/// # use appconfiguration::SubscriptionFilter;
+/// // Notify only changes in feature `f1` and property `p1`
+/// let filter = SubscriptionFilter::default().feature("f1").property("p1");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionFilter {
+    features: Option<HashSet<String>>,
+    properties: Option<HashSet<String>>,
+}
+
+impl SubscriptionFilter {
+    /// Adds a feature to the list of features to notify.
+    pub fn feature(mut self, feature_id: &str) -> Self {
+        self.features
+            .get_or_insert_with(HashSet::new)
+            .insert(feature_id.to_string());
+        self
+    }
+
+    /// Adds a property to the list of properties to notify.
+    pub fn property(mut self, property_id: &str) -> Self {
+        self.properties
+            .get_or_insert_with(HashSet::new)
+            .insert(property_id.to_string());
+        self
+    }
+}
+
+/// Callback invoked with the changes in the configuration.
+pub type ChangeCallback = Box<dyn FnMut(&ConfigurationChange) + Send>;
+
+enum Sink {
+    Channel(Sender<ConfigurationChange>),
+    Callback(ChangeCallback),
+}
+
+struct Subscriber {
+    filter: SubscriptionFilter,
+    sink: Sink,
+}
+
+/// Registry of the subscriptions made to a client.
+#[derive(Default)]
+pub(crate) struct Subscriptions {
+    subscribers: Mutex<Vec<Subscriber>>,
+}
+
+impl std::fmt::Debug for Subscriptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let count = self.subscribers.lock().map(|s| s.len()).unwrap_or_default();
+        f.debug_struct("Subscriptions")
+            .field("subscribers", &count)
+            .finish()
+    }
+}
+
+impl Subscriptions {
+    pub(crate) fn subscribe(
+        &self,
+        filter: SubscriptionFilter,
+    ) -> Result<Receiver<ConfigurationChange>> {
+        let (sender, receiver) = channel();
+        self.subscribers.lock()?.push(Subscriber {
+            filter,
+            sink: Sink::Channel(sender),
+        });
+        Ok(receiver)
+    }
+
+    pub(crate) fn subscribe_with_callback(
+        &self,
+        filter: SubscriptionFilter,
+        callback: ChangeCallback,
+    ) -> Result<()> {
+        self.subscribers.lock()?.push(Subscriber {
+            filter,
+            sink: Sink::Callback(callback),
+        });
+        Ok(())
+    }
+
+    /// Notifies every subscriber about the changes it is interested in. Subscribers
+    /// whose receiver has been dropped are removed.
+    ///
+    /// The subscribers are notified without holding the lock, so callbacks can subscribe
+    /// again. Subscriptions made meanwhile are kept, but not notified about this change.
+    pub(crate) fn notify(&self, change: &ConfigurationChange) -> Result<()> {
+        let mut subscribers = std::mem::take(&mut *self.subscribers.lock()?);
+        subscribers.retain_mut(|subscriber| {
+            let change = change.filtered(&subscriber.filter);
+            if change.is_empty() {
+                return true;
+            }
+            match &mut subscriber.sink {
+                Sink::Channel(sender) => sender.send(change).is_ok(),
+                Sink::Callback(callback) => {
+                    callback(&change);
+                    true
+                }
+            }
+        });
+        let mut current = self.subscribers.lock()?;
+        subscribers.append(&mut current);
+        *current = subscribers;
+        Ok(())
+    }
+}
+
+/// Replaces the latest configuration snapshot and notifies the subscribers about the changes.
+pub(crate) fn update_configuration_snapshot(
+    latest_config_snapshot: &Mutex<ConfigurationSnapshot>,
+    subscriptions: &Subscriptions,
+    config_snapshot: ConfigurationSnapshot,
+) -> Result<()> {
+    let change = {
+        let mut latest = latest_config_snapshot.lock()?;
+        let change = ConfigurationChange::between(&latest, &config_snapshot);
+        *latest = config_snapshot;
+        change
+    };
+    // Subscribers are notified without holding the snapshot lock, so they can query the client
+    if !change.is_empty() {
+        subscriptions.notify(&change)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::tests::{configuration_feature1_enabled, example_configuration_enterprise};
+    use crate::models::{Configuration, ValueFormat};
+    use rstest::*;
+    use std::sync::Arc;
+
+    #[rstest]
+    fn test_configuration_change(
+        example_configuration_enterprise: Configuration,
+        configuration_feature1_enabled: Configuration,
+    ) {
+        let old = ConfigurationSnapshot::new("dev", example_configuration_enterprise).unwrap();
+        let new =
+            ConfigurationSnapshot::new("environment_id", configuration_feature1_enabled).unwrap();
+
+        let change = ConfigurationChange::between(&old, &new);
+        assert_eq!(
+            change.features,
+            HashMap::from([
+                (
+                    "f1".to_string(),
+                    ChangeKind::Modified {
+                        value_changed: true,
+                        rules_changed: true
+                    }
+                ),
+                ("f2".to_string(), ChangeKind::Removed),
+                ("f3".to_string(), ChangeKind::Removed),
+                ("f4".to_string(), ChangeKind::Removed),
+                ("f5".to_string(), ChangeKind::Removed),
+                ("f6".to_string(), ChangeKind::Removed),
+            ])
+        );
+        assert_eq!(change.properties.len(), 4);
+        assert!(change
+            .properties
+            .values()
+            .all(|c| c == &ChangeKind::Removed));
+
+        let change = ConfigurationChange::between(&new, &old);
+        assert_eq!(change.features.get("f2"), Some(&ChangeKind::Added));
+        assert_eq!(change.properties.get("p1"), Some(&ChangeKind::Added));
+    }
+
+    #[rstest]
+    fn test_configuration_change_in_segment(example_configuration_enterprise: Configuration) {
+        let old = ConfigurationSnapshot::new("dev", example_configuration_enterprise).unwrap();
+        let mut new = ConfigurationSnapshot::new(
+            "dev",
+            crate::models::tests::example_configuration_enterprise(),
+        )
+        .unwrap();
+        assert!(ConfigurationChange::between(&old, &new).is_empty());
+
+        // Modify a segment: features and properties using it are affected
+        new.segments.get_mut("l2dfo8do").unwrap().rules[0].values = vec!["OTHER".into()];
+        let change = ConfigurationChange::between(&old, &new);
+        assert!(!change.is_empty());
+        for change in change.features.values().chain(change.properties.values()) {
+            assert_eq!(
+                change,
+                &ChangeKind::Modified {
+                    value_changed: false,
+                    rules_changed: true
+                }
+            );
+        }
+    }
+
+    #[rstest]
+    fn test_configuration_change_in_format(example_configuration_enterprise: Configuration) {
+        let old = ConfigurationSnapshot::new("dev", example_configuration_enterprise).unwrap();
+        let mut new = ConfigurationSnapshot::new(
+            "dev",
+            crate::models::tests::example_configuration_enterprise(),
+        )
+        .unwrap();
+
+        // Same raw values, parsed differently
+        new.features.get_mut("f2").unwrap().format = Some(ValueFormat::Json);
+        new.properties.get_mut("p2").unwrap().format = Some(ValueFormat::Yaml);
+        let change = ConfigurationChange::between(&old, &new);
+        let modified = ChangeKind::Modified {
+            value_changed: true,
+            rules_changed: false,
+        };
+        assert_eq!(
+            change.features,
+            HashMap::from([("f2".to_string(), modified.clone())])
+        );
+        assert_eq!(
+            change.properties,
+            HashMap::from([("p2".to_string(), modified)])
+        );
+    }
+
+    #[test]
+    fn test_subscription_filter() {
+        let change = ConfigurationChange {
+            features: HashMap::from([
+                ("f1".to_string(), ChangeKind::Added),
+                ("f2".to_string(), ChangeKind::Removed),
+            ]),
+            properties: HashMap::from([("p1".to_string(), ChangeKind::Added)]),
+        };
+
+        assert_eq!(change.filtered(&SubscriptionFilter::default()), change);
+
+        let filtered = change.filtered(&SubscriptionFilter::default().feature("f1"));
+        assert_eq!(filtered.features.keys().collect::<Vec<_>>(), vec!["f1"]);
+        assert!(filtered.properties.is_empty());
+
+        let filtered = change.filtered(&SubscriptionFilter::default().property("p1"));
+        assert!(filtered.features.is_empty());
+        assert_eq!(filtered.properties.keys().collect::<Vec<_>>(), vec!["p1"]);
+
+        let filtered = change.filtered(&SubscriptionFilter::default().feature("f3"));
+        assert!(filtered.is_empty());
+    }
+
+    #[test]
+    fn test_notify_subscribers() {
+        let subscriptions = Subscriptions::default();
+        let all = subscriptions
+            .subscribe(SubscriptionFilter::default())
+            .unwrap();
+        let only_f2 = subscriptions
+            .subscribe(SubscriptionFilter::default().feature("f2"))
+            .unwrap();
+        let dropped = subscriptions
+            .subscribe(SubscriptionFilter::default())
+            .unwrap();
+        drop(dropped);
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        subscriptions
+            .subscribe_with_callback(
+                SubscriptionFilter::default().feature("f1"),
+                Box::new(move |change| received_clone.lock().unwrap().push(change.clone())),
+            )
+            .unwrap();
+
+        let change = ConfigurationChange {
+            features: HashMap::from([("f1".to_string(), ChangeKind::Added)]),
+            properties: HashMap::new(),
+        };
+        subscriptions.notify(&change).unwrap();
+
+        assert_eq!(all.try_recv().unwrap(), change);
+        assert!(only_f2.try_recv().is_err());
+        assert_eq!(*received.lock().unwrap(), vec![change]);
+
+        // The subscriber with the dropped receiver has been removed
+        assert_eq!(subscriptions.subscribers.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_subscribe_from_callback() {
+        let subscriptions = Arc::new(Subscriptions::default());
+        let receivers = Arc::new(Mutex::new(Vec::new()));
+        let (subscriptions_clone, receivers_clone) = (subscriptions.clone(), receivers.clone());
+        subscriptions
+            .subscribe_with_callback(
+                SubscriptionFilter::default(),
+                Box::new(move |_| {
+                    let receiver = subscriptions_clone
+                        .subscribe(SubscriptionFilter::default())
+                        .unwrap();
+                    receivers_clone.lock().unwrap().push(receiver);
+                }),
+            )
+            .unwrap();
+
+        let change = ConfigurationChange {
+            features: HashMap::from([("f1".to_string(), ChangeKind::Added)]),
+            properties: HashMap::new(),
+        };
+        subscriptions.notify(&change).unwrap();
+        assert_eq!(subscriptions.subscribers.lock().unwrap().len(), 2);
+        // Subscribed while notifying: only receives the next change
+        assert!(receivers.lock().unwrap()[0].try_recv().is_err());
+
+        subscriptions.notify(&change).unwrap();
+        assert_eq!(subscriptions.subscribers.lock().unwrap().len(), 3);
+        assert_eq!(receivers.lock().unwrap()[0].try_recv().unwrap(), change);
+    }
+}
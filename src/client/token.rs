@@ -0,0 +1,231 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::client::http::AccessTokenResponse;
+use crate::errors::{Error, Result};
+
+/// Fraction of the token lifetime after which we request a new one, so the token
+/// is renewed before it actually expires.
+const REFRESH_AFTER_LIFETIME_FRACTION: f64 = 0.8;
+
+/// Lifetime assumed when the IAM response carries no expiration information.
+const DEFAULT_TOKEN_LIFETIME: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone)]
+pub(crate) struct AccessToken {
+    pub(crate) token: String,
+    pub(crate) expires_at: SystemTime,
+    pub(crate) refresh_at: SystemTime,
+}
+
+impl AccessToken {
+    pub(crate) fn new(response: AccessTokenResponse, now: SystemTime) -> Self {
+        // `expiration` is an absolute timestamp, `expires_in` is relative to the time of the request
+        let lifetime = match (response.expiration, response.expires_in) {
+            (Some(expiration), _) => (UNIX_EPOCH + Duration::from_secs(expiration))
+                .duration_since(now)
+                .unwrap_or_default(),
+            (None, Some(expires_in)) => Duration::from_secs(expires_in),
+            (None, None) => DEFAULT_TOKEN_LIFETIME,
+        };
+        Self {
+            token: response.access_token,
+            expires_at: now + lifetime,
+            refresh_at: now + lifetime.mul_f64(REFRESH_AFTER_LIFETIME_FRACTION),
+        }
+    }
+
+    pub(crate) fn needs_refresh(&self, now: SystemTime) -> bool {
+        now >= self.refresh_at
+    }
+
+    pub(crate) fn is_expired(&self, now: SystemTime) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Keeps the IAM access token for a given `apikey` and renews it before it expires.
+///
+/// The same instance is shared by every component of a client that talks to the
+/// server (initial fetch, background updates, websocket reconnections).
+#[derive(Debug)]
+pub(crate) struct TokenManager {
+    apikey: String,
+    access_token: Mutex<Option<AccessToken>>,
+}
+
+impl TokenManager {
+    pub(crate) fn new(apikey: &str) -> Self {
+        Self {
+            apikey: apikey.to_string(),
+            access_token: Mutex::new(None),
+        }
+    }
+
+    /// Returns a valid access token, requesting a new one with `fetch` if needed.
+    ///
+    /// If renewing the token fails but the current one has not expired yet, the
+    /// current token is returned.
+    pub(crate) fn get_access_token(
+        &self,
+        fetch: impl FnOnce(&str) -> Result<AccessTokenResponse>,
+    ) -> Result<String> {
+        let mut access_token = self.access_token.lock()?;
+        let now = SystemTime::now();
+        if let Some(token) = Self::fresh_token(&access_token, now) {
+            return Ok(token);
+        }
+        match fetch(&self.apikey) {
+            Ok(response) => {
+                let new_token = AccessToken::new(response, now);
+                let token = new_token.token.clone();
+                *access_token = Some(new_token);
+                Ok(token)
+            }
+            Err(e) => Self::unexpired_token_or(&access_token, now, e),
+        }
+    }
+
+    /// Async version of [`TokenManager::get_access_token`].
+    #[cfg(feature = "async")]
+    pub(crate) async fn get_access_token_async<F, Fut>(&self, fetch: F) -> Result<String>
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: std::future::Future<Output = Result<AccessTokenResponse>>,
+    {
+        let now = SystemTime::now();
+        if let Some(token) = Self::fresh_token(&*self.access_token.lock()?, now) {
+            return Ok(token);
+        }
+        match fetch(self.apikey.clone()).await {
+            Ok(response) => {
+                let new_token = AccessToken::new(response, now);
+                let token = new_token.token.clone();
+                *self.access_token.lock()? = Some(new_token);
+                Ok(token)
+            }
+            Err(e) => Self::unexpired_token_or(&*self.access_token.lock()?, now, e),
+        }
+    }
+
+    fn fresh_token(access_token: &Option<AccessToken>, now: SystemTime) -> Option<String> {
+        access_token
+            .as_ref()
+            .filter(|token| !token.needs_refresh(now))
+            .map(|token| token.token.clone())
+    }
+
+    fn unexpired_token_or(
+        access_token: &Option<AccessToken>,
+        now: SystemTime,
+        error: Error,
+    ) -> Result<String> {
+        match access_token {
+            Some(token) if !token.is_expired(now) => Ok(token.token.clone()),
+            _ => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(expires_in: Option<u64>, expiration: Option<u64>) -> AccessTokenResponse {
+        AccessTokenResponse {
+            access_token: "token".to_string(),
+            expires_in,
+            expiration,
+        }
+    }
+
+    #[test]
+    fn test_token_lifetime_from_expires_in() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        let token = AccessToken::new(response(Some(3600), None), now);
+        assert_eq!(token.expires_at, now + Duration::from_secs(3600));
+        assert_eq!(token.refresh_at, now + Duration::from_secs(2880));
+
+        assert!(!token.needs_refresh(now));
+        assert!(!token.needs_refresh(now + Duration::from_secs(2879)));
+        assert!(token.needs_refresh(now + Duration::from_secs(2880)));
+
+        assert!(!token.is_expired(now + Duration::from_secs(3599)));
+        assert!(token.is_expired(now + Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn test_token_lifetime_from_expiration() {
+        // `expiration` takes precedence over `expires_in`
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        let token = AccessToken::new(response(Some(3600), Some(2_000)), now);
+        assert_eq!(token.expires_at, now + Duration::from_secs(1_000));
+        assert_eq!(token.refresh_at, now + Duration::from_secs(800));
+    }
+
+    #[test]
+    fn test_token_already_expired() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        let token = AccessToken::new(response(None, Some(500)), now);
+        assert_eq!(token.expires_at, now);
+        assert!(token.needs_refresh(now));
+        assert!(token.is_expired(now));
+    }
+
+    #[test]
+    fn test_refresh_failure_uses_unexpired_token() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        let access_token = Some(AccessToken::new(response(Some(100), None), now));
+
+        // Token needs refresh but it is still valid
+        let later = now + Duration::from_secs(90);
+        let token =
+            TokenManager::unexpired_token_or(&access_token, later, Error::ClientNotConfigured);
+        assert_eq!(token.unwrap(), "token");
+
+        // Token already expired: the error is returned
+        let later = now + Duration::from_secs(100);
+        let token =
+            TokenManager::unexpired_token_or(&access_token, later, Error::ClientNotConfigured);
+        assert!(matches!(token.unwrap_err(), Error::ClientNotConfigured));
+
+        // No token at all
+        let token = TokenManager::unexpired_token_or(&None, later, Error::ClientNotConfigured);
+        assert!(matches!(token.unwrap_err(), Error::ClientNotConfigured));
+    }
+
+    #[test]
+    fn test_token_is_reused_until_refresh() {
+        let token_manager = TokenManager::new("apikey");
+        let token = token_manager.get_access_token(|apikey| {
+            assert_eq!(apikey, "apikey");
+            Ok(response(Some(3600), None))
+        });
+        assert_eq!(token.unwrap(), "token");
+
+        // The token is still fresh, it is not requested again
+        let token = token_manager.get_access_token(|_| Err(Error::ClientNotConfigured));
+        assert_eq!(token.unwrap(), "token");
+    }
+
+    #[test]
+    fn test_token_without_expiration_info() {
+        let now = UNIX_EPOCH;
+        let token = AccessToken::new(response(None, None), now);
+        assert_eq!(token.expires_at, now + DEFAULT_TOKEN_LIFETIME);
+    }
+}
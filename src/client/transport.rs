@@ -0,0 +1,310 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::net::TcpStream;
+
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use crate::client::endpoints::Endpoints;
+use crate::client::http::{self, HttpOptions};
+#[cfg(feature = "async")]
+use crate::client::http_async;
+use crate::errors::{Error, Result};
+
+/// The App Configuration instance, environment and collection a client reads
+/// the configuration from, and the endpoints to reach it.
+#[derive(Debug, Clone)]
+pub struct ServiceInstance {
+    region: String,
+    guid: String,
+    environment_id: String,
+    collection_id: String,
+    endpoints: Endpoints,
+}
+
+impl ServiceInstance {
+    pub(crate) fn new(
+        region: &str,
+        guid: &str,
+        environment_id: &str,
+        collection_id: &str,
+        endpoints: Endpoints,
+    ) -> Self {
+        Self {
+            region: region.to_string(),
+            guid: guid.to_string(),
+            environment_id: environment_id.to_string(),
+            collection_id: collection_id.to_string(),
+            endpoints,
+        }
+    }
+
+    /// Region where the App Configuration service instance is created.
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    /// Instance ID of the App Configuration service.
+    pub fn guid(&self) -> &str {
+        &self.guid
+    }
+
+    /// ID of the environment to read the configuration from.
+    pub fn environment_id(&self) -> &str {
+        &self.environment_id
+    }
+
+    /// ID of the collection to read the configuration from.
+    pub fn collection_id(&self) -> &str {
+        &self.collection_id
+    }
+
+    /// URL to request the configuration (without the query parameters).
+    pub fn configuration_url(&self) -> String {
+        self.endpoints
+            .get_configuration_url(&self.region, &self.guid)
+    }
+
+    /// URL to send the usage of features and properties.
+    pub fn usage_url(&self) -> String {
+        self.endpoints.get_usage_url(&self.region, &self.guid)
+    }
+
+    /// URL to open the websocket receiving live-updates (without the query parameters).
+    pub fn websocket_url(&self) -> String {
+        self.endpoints.get_websocket_url(&self.region)
+    }
+
+    /// URL to request IAM access tokens.
+    pub fn token_url(&self) -> String {
+        self.endpoints.get_token_url()
+    }
+}
+
+/// Network operations used by [`AppConfigurationClientIBMCloud`](crate::AppConfigurationClientIBMCloud)
+/// to talk to the server.
+///
+/// The default implementation is [`HttpTransport`]. Implement this trait to use your own
+/// networking stack (custom proxies, mTLS,...) or to serve the configuration from memory.
+pub trait Transport: Send + Sync + 'static {
+    /// Stream of notifications sent by the server when the configuration changes.
+    type UpdateStream: UpdateStream;
+
+    /// Requests an access token for `apikey` to the IAM service. Returns the body of the
+    /// response: a JSON document containing the `access_token` and, optionally, its
+    /// `expires_in` or `expiration`.
+    fn fetch_token(&self, instance: &ServiceInstance, apikey: &str) -> Result<String>;
+
+    /// Requests the configuration of the instance, environment and collection. Returns the
+    /// body of the response: a JSON document with the same format used to export the configuration.
+    fn fetch_configuration(&self, instance: &ServiceInstance, access_token: &str)
+        -> Result<String>;
+
+    /// Opens a stream to receive notifications when the configuration changes.
+    fn open_update_stream(
+        &self,
+        instance: &ServiceInstance,
+        access_token: &str,
+    ) -> Result<Self::UpdateStream>;
+
+    /// Sends the usage of features and properties recorded when usage metering is enabled.
+    /// `body` is the JSON document expected by the usage endpoint.
+    ///
+    /// Transports that don't implement it cannot be used with usage metering.
+    fn send_usage(&self, instance: &ServiceInstance, access_token: &str, body: &str) -> Result<()> {
+        let _ = (instance, access_token, body);
+        Err(Error::Other(
+            "Sending usage is not supported by this transport".into(),
+        ))
+    }
+}
+
+/// Notifications about changes in the configuration, see [`Transport::open_update_stream`].
+pub trait UpdateStream: Send + 'static {
+    /// Blocks until the server notifies that the configuration has changed.
+    ///
+    /// An error means that the stream is broken, the client will open a new one.
+    fn wait_for_update(&mut self) -> Result<()>;
+}
+
+/// [`Transport`] sending HTTP requests and receiving live-updates through a websocket.
+#[derive(Debug, Clone, Default)]
+pub struct HttpTransport {
+    pub(crate) http_options: HttpOptions,
+}
+
+impl HttpTransport {
+    pub(crate) fn new(http_options: HttpOptions) -> Self {
+        Self { http_options }
+    }
+}
+
+impl Transport for HttpTransport {
+    type UpdateStream = WebSocketUpdateStream;
+
+    fn fetch_token(&self, instance: &ServiceInstance, apikey: &str) -> Result<String> {
+        http::get_access_token(apikey, &instance.token_url(), &self.http_options)
+    }
+
+    fn fetch_configuration(
+        &self,
+        instance: &ServiceInstance,
+        access_token: &str,
+    ) -> Result<String> {
+        http::get_configuration(access_token, instance, &self.http_options)
+    }
+
+    fn open_update_stream(
+        &self,
+        instance: &ServiceInstance,
+        access_token: &str,
+    ) -> Result<Self::UpdateStream> {
+        let (socket, _response) = http::get_configuration_monitoring_websocket(
+            access_token,
+            instance,
+            &self.http_options,
+        )?;
+        Ok(WebSocketUpdateStream { socket })
+    }
+
+    fn send_usage(&self, instance: &ServiceInstance, access_token: &str, body: &str) -> Result<()> {
+        http::send_usage(access_token, instance, body, &self.http_options)
+    }
+}
+
+/// Async counterpart of [`Transport`], used by
+/// [`AppConfigurationClientIBMCloudAsync`](crate::AppConfigurationClientIBMCloudAsync)
+/// to fetch the configuration and receive live-updates without blocking the runtime.
+///
+/// The blocking operations of [`Transport`] are still used from background threads
+/// (usage metering), so implementors provide both.
+#[cfg(feature = "async")]
+pub trait AsyncTransport: Transport {
+    /// Stream of notifications sent by the server when the configuration changes.
+    type AsyncUpdateStream: AsyncUpdateStream;
+
+    /// See [`Transport::fetch_token`].
+    fn fetch_token_async(
+        &self,
+        instance: &ServiceInstance,
+        apikey: &str,
+    ) -> impl Future<Output = Result<String>> + Send;
+
+    /// See [`Transport::fetch_configuration`].
+    fn fetch_configuration_async(
+        &self,
+        instance: &ServiceInstance,
+        access_token: &str,
+    ) -> impl Future<Output = Result<String>> + Send;
+
+    /// See [`Transport::open_update_stream`].
+    fn open_update_stream_async(
+        &self,
+        instance: &ServiceInstance,
+        access_token: &str,
+    ) -> impl Future<Output = Result<Self::AsyncUpdateStream>> + Send;
+}
+
+/// Async counterpart of [`UpdateStream`], see [`AsyncTransport`].
+#[cfg(feature = "async")]
+pub trait AsyncUpdateStream: Send + 'static {
+    /// Waits until the server notifies that the configuration has changed.
+    fn wait_for_update(&mut self) -> impl Future<Output = Result<()>> + Send;
+}
+
+#[cfg(feature = "async")]
+impl AsyncTransport for HttpTransport {
+    type AsyncUpdateStream = AsyncWebSocketUpdateStream;
+
+    async fn fetch_token_async(&self, instance: &ServiceInstance, apikey: &str) -> Result<String> {
+        http_async::get_access_token(apikey, &instance.token_url(), &self.http_options).await
+    }
+
+    async fn fetch_configuration_async(
+        &self,
+        instance: &ServiceInstance,
+        access_token: &str,
+    ) -> Result<String> {
+        http_async::get_configuration(access_token, instance, &self.http_options).await
+    }
+
+    async fn open_update_stream_async(
+        &self,
+        instance: &ServiceInstance,
+        access_token: &str,
+    ) -> Result<Self::AsyncUpdateStream> {
+        let (socket, _response) = http_async::get_configuration_monitoring_websocket(
+            access_token,
+            instance,
+            &self.http_options,
+        )
+        .await?;
+        Ok(AsyncWebSocketUpdateStream { socket })
+    }
+}
+
+/// [`AsyncUpdateStream`] used by [`HttpTransport`].
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncWebSocketUpdateStream {
+    socket: tokio_tungstenite::WebSocketStream<
+        tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
+    >,
+}
+
+#[cfg(feature = "async")]
+impl AsyncUpdateStream for AsyncWebSocketUpdateStream {
+    async fn wait_for_update(&mut self) -> Result<()> {
+        use futures_util::StreamExt;
+
+        while let Some(message) = self.socket.next().await {
+            match message? {
+                Message::Text(text) => match text.as_str() {
+                    "test message" => {} // periodically sent by the server
+                    _ => return Ok(()),
+                },
+                Message::Close(_) => break,
+                _ => {}
+            }
+        }
+        Err(Error::Other("Connection closed by the server".into()))
+    }
+}
+
+/// [`UpdateStream`] used by [`HttpTransport`].
+#[derive(Debug)]
+pub struct WebSocketUpdateStream {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+}
+
+impl UpdateStream for WebSocketUpdateStream {
+    fn wait_for_update(&mut self) -> Result<()> {
+        loop {
+            // read() blocks until something happens.
+            match self.socket.read()? {
+                Message::Text(text) => match text.as_str() {
+                    "test message" => {} // periodically sent by the server
+                    _ => return Ok(()),
+                },
+                Message::Close(_) => {
+                    return Err(Error::Other("Connection closed by the server".into()));
+                }
+                _ => {}
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::sync::PoisonError;
 
 use thiserror::Error;
@@ -32,7 +33,7 @@ pub enum Error {
     ReqwestError(#[from] reqwest::Error),
 
     #[error(transparent)]
-    TungsteniteError(#[from] tungstenite::Error),
+    TungsteniteError(#[from] Box<tungstenite::Error>),
 
     #[error("Protocol error. Unexpected data received from server")]
     ProtocolError(String),
@@ -40,6 +41,15 @@ pub enum Error {
     #[error(transparent)]
     DeserializationError(#[from] DeserializationError),
 
+    #[error("Cannot parse configuration: {0}")]
+    ConfigurationParseError(serde_json::Error),
+
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+
+    #[error(transparent)]
+    BuilderError(#[from] BuilderError),
+
     #[error("Client is not configured")]
     ClientNotConfigured,
 
@@ -49,6 +59,19 @@ pub enum Error {
     #[error("Failed to evaluate entity: {0}")]
     EntityEvaluationError(EntityEvaluationError),
 
+    #[error("Values were evaluated for entity '{expected_id}', not for entity '{entity_id}'")]
+    EntityMismatch {
+        expected_id: String,
+        entity_id: String,
+    },
+
+    #[error("Invalid rollout percentage '{value}' in segment rule '{rule_order}' of feature '{feature_id}'")]
+    InvalidRolloutPercentage {
+        feature_id: String,
+        rule_order: u32,
+        value: String,
+    },
+
     #[error("{0}")]
     Other(String),
 }
@@ -63,6 +86,12 @@ impl From<SegmentEvaluationError> for Error {
     }
 }
 
+impl From<tungstenite::Error> for Error {
+    fn from(value: tungstenite::Error) -> Self {
+        Self::TungsteniteError(Box::new(value))
+    }
+}
+
 impl<T> From<PoisonError<T>> for Error {
     fn from(_value: PoisonError<T>) -> Self {
         Error::CannotAcquireLock
@@ -82,6 +111,42 @@ pub struct DeserializationError {
 pub enum DeserializationErrorKind {
     #[error(transparent)]
     SerdeError(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    YamlError(#[from] serde_yaml::Error),
+}
+
+/// Invalid settings given to [`ClientBuilder`](crate::ClientBuilder).
+#[derive(Debug, Error, PartialEq)]
+pub enum BuilderError {
+    #[error("Missing required setting '{0}'")]
+    MissingSetting(&'static str),
+
+    #[error("Setting '{0}' cannot be empty")]
+    EmptySetting(&'static str),
+
+    #[error("Invalid URL '{url}' for setting '{setting}': {reason}")]
+    InvalidUrl {
+        setting: &'static str,
+        url: String,
+        reason: String,
+    },
+
+    #[error("Invalid path '{path}' for setting '{setting}': {reason}")]
+    InvalidPath {
+        setting: &'static str,
+        path: PathBuf,
+        reason: String,
+    },
+
+    #[error("Setting '{0}' must be greater than zero")]
+    ZeroDuration(&'static str),
+
+    #[error("Invalid value for setting '{setting}': {reason}")]
+    InvalidSetting {
+        setting: &'static str,
+        reason: String,
+    },
 }
 
 #[derive(Debug, Error)]
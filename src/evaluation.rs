@@ -0,0 +1,109 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use crate::{Error, Value};
+
+/// Explains why an evaluation returned its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvaluationReason {
+    /// The feature is disabled, so its disabled value is returned.
+    Disabled,
+    /// A targeting rule matched the entity and its value is returned.
+    TargetingMatch,
+    /// The entity is not part of the rollout percentage, so the feature's disabled
+    /// value is returned.
+    RolloutExcluded,
+    /// No targeting rule matched the entity, the default value is returned (the
+    /// enabled value for features).
+    Default,
+}
+
+/// The value resulting from evaluating a feature or property for an entity,
+/// together with the information that lead to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvaluationDetails {
+    /// The evaluated value.
+    pub value: Value,
+    /// Why this value was returned.
+    pub reason: EvaluationReason,
+    /// The `order` of the targeting rule that matched the entity, if any.
+    pub rule_order: Option<u32>,
+    /// The id of the segment that matched the entity, if any.
+    pub segment_id: Option<String>,
+    /// The rollout percentage applied to the entity. Only available for enabled features.
+    pub rollout_percentage: Option<u32>,
+}
+
+impl EvaluationDetails {
+    pub(crate) fn new(value: Value, reason: EvaluationReason) -> Self {
+        Self {
+            value,
+            reason,
+            rule_order: None,
+            segment_id: None,
+            rollout_percentage: None,
+        }
+    }
+
+    pub(crate) fn with_targeting_rule(mut self, rule_order: u32, segment_id: String) -> Self {
+        self.rule_order = Some(rule_order);
+        self.segment_id = Some(segment_id);
+        self
+    }
+
+    pub(crate) fn with_rollout_percentage(mut self, rollout_percentage: u32) -> Self {
+        self.rollout_percentage = Some(rollout_percentage);
+        self
+    }
+}
+
+/// All the features and properties of a configuration evaluated for the same entity.
+///
+/// Returned by [`AppConfigurationClient::evaluate_all`](crate::AppConfigurationClient::evaluate_all).
+///
+/// Features and properties are evaluated independently: the ones that fail to evaluate
+/// (e.g. because the entity lacks an attribute required by a segment) are reported in
+/// `feature_errors` and `property_errors` instead of the details maps.
+#[derive(Debug, Default)]
+pub struct EvaluatedConfiguration {
+    /// Evaluation details of each feature, by feature id.
+    pub features: HashMap<String, EvaluationDetails>,
+    /// Evaluation details of each property, by property id.
+    pub properties: HashMap<String, EvaluationDetails>,
+    /// Error of each feature that failed to evaluate, by feature id.
+    pub feature_errors: HashMap<String, Error>,
+    /// Error of each property that failed to evaluate, by property id.
+    pub property_errors: HashMap<String, Error>,
+}
+
+impl EvaluatedConfiguration {
+    /// Returns the evaluated value of each feature, by feature id.
+    pub fn feature_values(&self) -> HashMap<String, Value> {
+        Self::values(&self.features)
+    }
+
+    /// Returns the evaluated value of each property, by property id.
+    pub fn property_values(&self) -> HashMap<String, Value> {
+        Self::values(&self.properties)
+    }
+
+    fn values(details: &HashMap<String, EvaluationDetails>) -> HashMap<String, Value> {
+        details
+            .iter()
+            .map(|(id, details)| (id.clone(), details.value.clone()))
+            .collect()
+    }
+}
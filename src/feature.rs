@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use serde::de::DeserializeOwned;
+
 use crate::errors::Result;
 use crate::{Entity, Value};
 
@@ -21,18 +23,18 @@ pub trait Feature {
     fn get_name(&self) -> Result<String>;
 
     /// Returns if the feature is enabled or not.
-    /// 
-    /// An enabled feature should be evaluated for each [`Entity`] to get the 
+    ///
+    /// An enabled feature should be evaluated for each [`Entity`] to get the
     /// corresponding value. However, disabled features won't be evaluated and
     /// will always return the disabled value.
     fn is_enabled(&self) -> Result<bool>;
 
     /// Evaluates a feature for the given [`Entity`] and returns a [`Value`].
-    /// 
+    ///
     /// Use the methods available in [`Value`] to return the actual primitive value. If
     /// all you want is the primitive value, you can use the method
     /// [`get_value_into`](Feature::get_value_into) instead.
-    /// 
+    ///
     /// # Examples
     ///
     /// ```
@@ -40,13 +42,14 @@ pub trait Feature {
     /// # fn doctest_get_value(client: impl AppConfigurationClient, entity: &impl Entity) -> Result<()> {
     ///     let feature = client.get_feature("my_feature")?;
     ///     let value: Value = feature.get_value(entity)?;
-    /// 
+    ///
     ///     match value {
     ///         Value::Float64(v) => println!("f64 with value {v}"),
     ///         Value::UInt64(v) => println!("u64 with value {v}"),
     ///         Value::Int64(v) => println!("i64 with value {v}"),
     ///         Value::String(v) => println!("String with value {v}"),
     ///         Value::Boolean(v) => println!("bool with value {v}"),
+    ///         Value::Structured(v) => println!("JSON or YAML data {v}"),
     ///     }
     /// #   Ok(())
     /// # }
@@ -55,7 +58,11 @@ pub trait Feature {
 
     /// Evaluates a feature for the given [`Entity`] and returns its value converted (if possible)
     /// to the given type.
-    /// 
+    ///
+    /// The conversion uses [`TryFrom<Value>`](Value), implemented for the primitive types and,
+    /// for `JSON` and `YAML` values, [`serde_json::Value`]. Use
+    /// [`get_value_as`](Feature::get_value_as) to deserialize into any [`serde::Deserialize`] type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -63,7 +70,7 @@ pub trait Feature {
     /// # fn doctest_get_value_into(client: impl AppConfigurationClient, entity: &impl Entity) -> Result<()> {
     ///     let feature = client.get_feature("my_f64_feature")?;
     ///     let value: f64 = feature.get_value_into(entity)?;
-    /// 
+    ///
     ///     // an f64 cannot be returned as u64
     ///     let failed: Result<u64> = feature.get_value_into(entity);
     ///     assert!(failed.is_err());
@@ -74,4 +81,29 @@ pub trait Feature {
         &self,
         entity: &impl Entity,
     ) -> Result<T>;
+
+    /// Evaluates a feature for the given [`Entity`] and deserializes its value into `T`.
+    ///
+    /// Any type implementing [`serde::Deserialize`] can be used, which is convenient for
+    /// values with `JSON` or `YAML` format. If the value cannot be deserialized, an
+    /// [`Error::DeserializationError`](crate::Error::DeserializationError) is returned
+    /// containing the evaluated value as a string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use appconfiguration::{AppConfigurationClient, Feature, Result, Entity};
+    /// # fn doctest_get_value_as(client: impl AppConfigurationClient, entity: &impl Entity) -> Result<()> {
+    ///     #[derive(serde::Deserialize)]
+    ///     struct PoolSettings {
+    ///         size: u32,
+    ///         timeout_secs: Option<u64>,
+    ///     }
+    ///
+    ///     let feature = client.get_feature("my_pool_feature")?;
+    ///     let settings: PoolSettings = feature.get_value_as(entity)?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    fn get_value_as<T: DeserializeOwned>(&self, entity: &impl Entity) -> Result<T>;
 }
@@ -0,0 +1,124 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::errors::{Error, Result};
+use crate::{Entity, EvaluationDetails};
+
+/// Whether a feature or a property is being evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    /// A feature flag, evaluated with [`Feature`](crate::Feature).
+    Feature,
+    /// A property, evaluated with [`Property`](crate::Property).
+    Property,
+}
+
+/// Describes the evaluation the hooks are called for.
+pub struct HookContext<'a> {
+    /// Whether [`id`](HookContext::id) is a feature or a property.
+    pub kind: ResourceKind,
+    /// ID of the feature or property being evaluated.
+    pub id: &'a str,
+    /// Entity the feature or property is evaluated for.
+    pub entity: &'a dyn Entity,
+    /// When the evaluation started, right before calling the `before` hooks.
+    pub started_at: Instant,
+}
+
+/// Code run around the evaluation of features and properties, for example to write
+/// audit logs, send exposure events or track latencies.
+///
+/// Hooks are registered on the client with [`ClientBuilder::hook`](crate::ClientBuilder::hook)
+/// (or [`AppConfigurationOffline::with_hook`](crate::AppConfigurationOffline::with_hook)) and
+/// run every time a feature or property returned by the client, or its proxy, is evaluated.
+/// They run in the thread evaluating the value, so they should return quickly.
+///
+/// # Examples
+///
+/// ```
+/// # use appconfiguration::{Error, EvaluationDetails, EvaluationHook, HookContext};
+/// #[derive(Debug)]
+/// struct AuditHook;
+///
+/// impl EvaluationHook for AuditHook {
+///     fn after(&self, context: &HookContext<'_>, details: &EvaluationDetails) {
+///         println!(
+///             "'{}' evaluated to {:?} for '{}' ({:?}) in {:?}",
+///             context.id,
+///             details.value,
+///             context.entity.get_id(),
+///             details.reason,
+///             context.started_at.elapsed()
+///         );
+///     }
+///
+///     fn error(&self, context: &HookContext<'_>, error: &Error) {
+///         println!("'{}' failed to evaluate: {error}", context.id);
+///     }
+/// }
+/// ```
+pub trait EvaluationHook: Debug + Send + Sync {
+    /// Called before evaluating the feature or property.
+    fn before(&self, _context: &HookContext<'_>) {}
+
+    /// Called after evaluating the feature or property, with the resulting value and reason.
+    fn after(&self, _context: &HookContext<'_>, _details: &EvaluationDetails) {}
+
+    /// Called when the feature or property cannot be evaluated.
+    fn error(&self, _context: &HookContext<'_>, _error: &Error) {}
+}
+
+/// The hooks registered on a client, shared with the features and properties it returns.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Hooks(Vec<Arc<dyn EvaluationHook>>);
+
+impl Hooks {
+    pub(crate) fn new(hooks: Vec<Arc<dyn EvaluationHook>>) -> Self {
+        Self(hooks)
+    }
+
+    pub(crate) fn push(&mut self, hook: Arc<dyn EvaluationHook>) {
+        self.0.push(hook);
+    }
+
+    /// Runs `evaluate` calling the hooks before and after it.
+    pub(crate) fn run(
+        &self,
+        kind: ResourceKind,
+        id: &str,
+        entity: &impl Entity,
+        evaluate: impl FnOnce() -> Result<EvaluationDetails>,
+    ) -> Result<EvaluationDetails> {
+        if self.0.is_empty() {
+            return evaluate();
+        }
+        let context = HookContext {
+            kind,
+            id,
+            entity,
+            started_at: Instant::now(),
+        };
+        self.0.iter().for_each(|hook| hook.before(&context));
+        let result = evaluate();
+        match &result {
+            Ok(details) => self.0.iter().for_each(|hook| hook.after(&context, details)),
+            Err(e) => self.0.iter().for_each(|hook| hook.error(&context, e)),
+        }
+        result
+    }
+}
@@ -80,19 +80,156 @@
 //! # }
 //! ```
 //!
+//! Use [`ClientBuilder`] to name each setting and configure timeouts, a proxy, custom
+//! [`Endpoints`] (private network, staging IAM or a local server), local copies of the
+//! configuration or to disable live-updates. The settings are validated when the client is built:
+//!
+//! ```no_run
+//! # use appconfiguration::{ClientBuilder, Result};
+//! # fn func() -> Result<()> {
+//! let client = ClientBuilder::new()
+//!     .apikey("api_key")
+//!     .region("us-south")
+//!     .guid("12345678-1234-1234-1234-12345678abcd")
+//!     .environment_id("production")
+//!     .collection_id("ecommerce")
+//!     .live_updates(false)
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! To use your own networking stack (custom proxies, mTLS,...) implement the [`Transport`] trait
+//! and create the client with [`ClientBuilder::build_with_transport`]. The async client takes an
+//! `AsyncTransport` (which also implements [`Transport`]) in `ClientBuilder::build_async_with_transport`.
+//!
+//! # Usage metering
+//!
+//! Enable [`ClientBuilder::usage_metering`] to see in the App Configuration dashboard how
+//! many times each feature and property is evaluated. The evaluations are aggregated in
+//! memory and a background thread sends them every
+//! [`metering_interval`](ClientBuilder::metering_interval).
+//!
+//! # Evaluation hooks
+//!
+//! Implement [`EvaluationHook`] and register it with [`ClientBuilder::hook`] to run your own
+//! code (audit logs, exposure events,...) before and after every evaluation of the features
+//! and properties returned by the client, including their proxies. The hooks receive the
+//! feature or property id, the entity and either the [`EvaluationDetails`] or the error.
+//!
+//! # Logging
+//!
+//! Diagnostics are emitted as [`tracing`](https://docs.rs/tracing) events, install a
+//! subscriber to collect them. The events and spans of a client (`fetch_configuration`,
+//! `reconnect`) are children of an `appconfiguration` span with the `guid`, `environment_id`
+//! and `collection_id` fields. Evaluations run in `evaluate_feature` and `evaluate_property`
+//! spans at the `TRACE` level.
+//!
+//! # Metrics
+//!
+//! With the `metrics` cargo feature, the clients record metrics through the
+//! [`metrics`](https://docs.rs/metrics) facade, to be exported by any compatible recorder
+//! (e.g. Prometheus):
+//!
+//! * `appconfiguration_evaluations_total` (`kind`, `id`, `reason`) and
+//!   `appconfiguration_evaluation_errors_total` (`kind`, `id`, `error`) counters, and
+//!   `appconfiguration_evaluation_duration_seconds` histogram (`kind`, `id`).
+//! * `appconfiguration_configuration_fetch_duration_seconds` histogram and
+//!   `appconfiguration_configuration_fetch_failures_total` counter (`environment_id`, `collection_id`).
+//! * `appconfiguration_reconnects_total` counter (`environment_id`, `collection_id`, `result`).
+//! * `appconfiguration_snapshot_age_seconds` gauge (`environment_id`), updated when the
+//!   configuration is read or fetched, and whenever the live-updates loop wakes up.
+//!
+//! # Async support
+//!
+//! [`AppConfigurationClientIBMCloud`] uses blocking requests and a dedicated thread to
+//! receive live-updates. Applications running inside a `tokio` runtime can enable the
+//! `async` cargo feature and use `AppConfigurationClientIBMCloudAsync` instead: it is
+//! created with an `async fn new` taking the same arguments, and it monitors the
+//! configuration in a task spawned in the current runtime.
+//!
+//! # OpenFeature
+//!
+//! Enable the `openfeature` cargo feature to use any [`AppConfigurationClient`] as an
+//! [OpenFeature](https://openfeature.dev) provider, `AppConfigurationProvider`. Flag keys are
+//! feature ids or, if there is no such feature, property ids. The targeting key of the
+//! evaluation context is used as the entity id and its custom fields as the entity attributes.
+//!
+//! # Offline usage
+//!
+//! For air-gapped environments or tests, [`AppConfigurationOffline`] evaluates features and
+//! properties using a configuration exported from the App Configuration service. It implements
+//! [`AppConfigurationClient`] too, so the rest of your code doesn't need to change:
+//!
+//! ```no_run
+//! # use appconfiguration::{AppConfigurationClient, AppConfigurationOffline, Result};
+//! # fn func() -> Result<()> {
+//! let client = AppConfigurationOffline::from_path("config-export.json", "production")?;
+//! let feature = client.get_feature("AB_testing_feature")?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The same exported file can be given to [`AppConfigurationClientIBMCloud`] as
+//! [`ClientOptions::bootstrap_file`]. Together with [`ClientOptions::cache_file`], where every
+//! configuration fetched from the server is stored, the client can start even if the server
+//! is not reachable, and switches to live data once the connection is established.
+//!
+//! # Client-side applications
+//!
+//! Browsers and mobile apps should not receive the segments and targeting rules. Instead,
+//! evaluate everything for their user on the server with
+//! [`AppConfigurationClient::evaluate_bootstrap`], send the resulting [`EvaluatedBootstrap`]
+//! (it implements `serde::Serialize`), and read it with [`AppConfigurationEvaluated`]:
+//!
+//! ```
+//! # use appconfiguration::{AppConfigurationClient, AppConfigurationEvaluated, Entity, Feature, Result};
+//! # fn func(client: impl AppConfigurationClient, user: &impl Entity) -> Result<()> {
+//! // On the server
+//! let bootstrap = client.evaluate_bootstrap(user)?;
+//! let json = serde_json::to_string(&bootstrap).expect("serializable");
+//!
+//! // On the client
+//! let evaluated = AppConfigurationEvaluated::from_json_str(&json)?;
+//! let enabled = evaluated.get_feature("AB_testing_feature")?.is_enabled()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+
 mod client;
 mod entity;
 mod errors;
+mod evaluation;
 mod feature;
+mod hooks;
 mod models;
+#[cfg(feature = "openfeature")]
+mod openfeature;
 mod property;
 mod segment_evaluation;
 mod value;
 
-pub use client::{AppConfigurationClient, AppConfigurationClientIBMCloud};
+pub use client::{
+    AppConfigurationClient, AppConfigurationClientIBMCloud, AppConfigurationEvaluated,
+    AppConfigurationOffline, ChangeCallback, ChangeKind, ClientBuilder, ClientOptions,
+    ConfigurationChange, ConnectionState, Endpoints, EvaluatedBootstrap, EvaluatedFeature,
+    EvaluatedFeatureSnapshot, EvaluatedProperty, EvaluatedPropertySnapshot, HttpTransport,
+    ReconnectPolicy, ServiceInstance, SubscriptionFilter, Transport, UpdateStream,
+    WebSocketUpdateStream,
+};
+#[cfg(feature = "async")]
+pub use client::{
+    AppConfigurationClientIBMCloudAsync, AsyncTransport, AsyncUpdateStream,
+    AsyncWebSocketUpdateStream,
+};
 pub use entity::Entity;
-pub use errors::{Error, Result};
+pub use errors::{BuilderError, Error, Result};
+pub use evaluation::{EvaluatedConfiguration, EvaluationDetails, EvaluationReason};
 pub use feature::Feature;
+pub use hooks::{EvaluationHook, HookContext, ResourceKind};
+#[cfg(feature = "openfeature")]
+pub use openfeature::AppConfigurationProvider;
 pub use property::Property;
 pub use value::Value;
 
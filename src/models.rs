@@ -13,18 +13,21 @@
 // limitations under the License.
 
 use std::fmt::Display;
+use std::sync::Arc;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
+use crate::errors::DeserializationError;
+use crate::segment_evaluation::CompiledValues;
 use crate::Value;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct Configuration {
     pub environments: Vec<Environment>,
     pub segments: Vec<Segment>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub(crate) struct Environment {
     #[serde(rename = "name")]
     _name: String,
@@ -33,7 +36,7 @@ pub(crate) struct Environment {
     pub properties: Vec<Property>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub(crate) struct Segment {
     #[serde(rename = "name")]
     pub _name: String,
@@ -45,22 +48,27 @@ pub(crate) struct Segment {
     pub rules: Vec<SegmentRule>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub(crate) struct Feature {
     pub name: String,
     pub feature_id: String,
     #[serde(rename = "type")]
     pub kind: ValueKind,
-    #[serde(rename = "format")]
-    pub _format: Option<String>,
+    pub format: Option<ValueFormat>,
     pub enabled_value: ConfigValue,
     pub disabled_value: ConfigValue,
     pub segment_rules: Vec<TargetingRule>,
     pub enabled: bool,
+    // Not present in lite plan configurations, which do not support percentage rollouts.
+    #[serde(default = "default_rollout_percentage")]
     pub rollout_percentage: u32,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+fn default_rollout_percentage() -> u32 {
+    100
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub(crate) struct Property {
     pub name: String,
     pub property_id: String,
@@ -68,22 +76,36 @@ pub(crate) struct Property {
     pub kind: ValueKind,
     #[serde(rename = "tags")]
     pub _tags: Option<String>,
-    #[serde(rename = "format")]
-    pub _format: Option<String>,
+    pub format: Option<ValueFormat>,
     pub value: ConfigValue,
     pub segment_rules: Vec<TargetingRule>,
 }
 
-#[derive(Copy, Clone, Debug, Deserialize, PartialEq)]
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub(crate) enum ValueKind {
-    #[serde(rename(deserialize = "NUMERIC"))]
+    #[serde(rename = "NUMERIC")]
     Numeric,
-    #[serde(rename(deserialize = "BOOLEAN"))]
+    #[serde(rename = "BOOLEAN")]
     Boolean,
-    #[serde(rename(deserialize = "STRING"))]
+    #[serde(rename = "STRING")]
     String,
 }
 
+/// Format of the values of `STRING` features and properties.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub(crate) enum ValueFormat {
+    #[serde(rename = "TEXT")]
+    Text,
+    #[serde(rename = "JSON")]
+    Json,
+    #[serde(rename = "YAML")]
+    Yaml,
+    /// Any other format, added to the service after this version of the crate. Values
+    /// are used as plain text.
+    #[serde(rename = "UNKNOWN", other)]
+    Unknown,
+}
+
 impl Display for ValueKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let label = match self {
@@ -95,7 +117,7 @@ impl Display for ValueKind {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub(crate) struct ConfigValue(pub(crate) serde_json::Value);
 
 impl ConfigValue {
@@ -134,11 +156,11 @@ impl Display for ConfigValue {
     }
 }
 
-impl TryFrom<(ValueKind, ConfigValue)> for Value {
+impl TryFrom<(ValueKind, Option<ValueFormat>, ConfigValue)> for Value {
     type Error = crate::Error;
 
-    fn try_from(value: (ValueKind, ConfigValue)) -> Result<Self, Self::Error> {
-        let (kind, value) = value;
+    fn try_from(value: (ValueKind, Option<ValueFormat>, ConfigValue)) -> Result<Self, Self::Error> {
+        let (kind, format, value) = value;
         match kind {
             ValueKind::Numeric => {
                 if let Some(n) = value.as_i64() {
@@ -157,22 +179,67 @@ impl TryFrom<(ValueKind, ConfigValue)> for Value {
                 .as_boolean()
                 .map(Value::Boolean)
                 .ok_or(crate::Error::MismatchType),
-            ValueKind::String => value
-                .as_string()
-                .map(Value::String)
-                .ok_or(crate::Error::MismatchType),
+            ValueKind::String => match format {
+                None | Some(ValueFormat::Text) | Some(ValueFormat::Unknown) => value
+                    .as_string()
+                    .map(Value::String)
+                    .ok_or(crate::Error::MismatchType),
+                // JSON values are usually given as JSON data, but they might come as a string too
+                Some(ValueFormat::Json) => match value.0 {
+                    serde_json::Value::String(string) => serde_json::from_str(&string)
+                        .map(Value::Structured)
+                        .map_err(|e| {
+                            DeserializationError {
+                                string,
+                                source: e.into(),
+                            }
+                            .into()
+                        }),
+                    data => Ok(Value::Structured(data)),
+                },
+                Some(ValueFormat::Yaml) => {
+                    let string = value.as_string().ok_or(crate::Error::MismatchType)?;
+                    serde_yaml::from_str(&string)
+                        .map(Value::Structured)
+                        .map_err(|e| {
+                            DeserializationError {
+                                string,
+                                source: e.into(),
+                            }
+                            .into()
+                        })
+                }
+            },
         }
     }
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub(crate) struct SegmentRule {
     pub attribute_name: String,
     pub operator: String,
     pub values: Vec<String>,
+    /// Set by [`SegmentRule::precompile`] for the operators that need it.
+    #[serde(skip)]
+    pub compiled_values: Option<Arc<CompiledValues>>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl SegmentRule {
+    pub fn precompile(&mut self) {
+        self.compiled_values = CompiledValues::new(&self.operator, &self.values).map(Arc::new);
+    }
+}
+
+// The compiled values are derived from the others, so they are not compared.
+impl PartialEq for SegmentRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.attribute_name == other.attribute_name
+            && self.operator == other.operator
+            && self.values == other.values
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub(crate) struct TargetingRule {
     pub rules: Vec<Segments>,
     pub value: ConfigValue,
@@ -180,7 +247,7 @@ pub(crate) struct TargetingRule {
     pub rollout_percentage: Option<ConfigValue>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub(crate) struct Segments {
     pub segments: Vec<String>,
 }
@@ -205,6 +272,15 @@ pub(crate) mod tests {
         configuration
     }
 
+    #[fixture]
+    pub(crate) fn example_configuration_lite() -> Configuration {
+        let mut mocked_data = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        mocked_data.push("data/data-dump-lite-plan-sdk-testing.json");
+
+        let content = fs::File::open(mocked_data).expect("file should open read only");
+        serde_json::from_reader(content).expect("Error parsing JSON into Configuration")
+    }
+
     #[fixture]
     pub(crate) fn configuration_feature1_enabled() -> Configuration {
         Configuration {
@@ -215,7 +291,7 @@ pub(crate) mod tests {
                     name: "F1".to_string(),
                     feature_id: "f1".to_string(),
                     kind: ValueKind::Numeric,
-                    _format: None,
+                    format: None,
                     enabled_value: ConfigValue(serde_json::Value::Number(42.into())),
                     disabled_value: ConfigValue(serde_json::Value::Number((-42).into())),
                     segment_rules: Vec::new(),
@@ -238,7 +314,7 @@ pub(crate) mod tests {
                     name: "P1".to_string(),
                     property_id: "p1".to_string(),
                     kind: ValueKind::Numeric,
-                    _format: None,
+                    format: None,
                     value: ConfigValue(serde_json::Value::Number(42.into())),
                     segment_rules: Vec::new(),
                     _tags: None,
@@ -248,4 +324,57 @@ pub(crate) mod tests {
             segments: Vec::new(),
         }
     }
+
+    #[rstest]
+    #[case(None, serde_json::json!("text"), Value::String("text".into()))]
+    #[case(Some(ValueFormat::Text), serde_json::json!("{}"), Value::String("{}".into()))]
+    #[case(Some(ValueFormat::Json), serde_json::json!({"a": [1, 2]}), Value::Structured(serde_json::json!({"a": [1, 2]})))]
+    #[case(Some(ValueFormat::Json), serde_json::json!("{\"a\": [1, 2]}"), Value::Structured(serde_json::json!({"a": [1, 2]})))]
+    #[case(Some(ValueFormat::Yaml), serde_json::json!("a:\n  - 1\n  - 2\n"), Value::Structured(serde_json::json!({"a": [1, 2]})))]
+    fn test_string_value_formats(
+        #[case] format: Option<ValueFormat>,
+        #[case] value: serde_json::Value,
+        #[case] expected: Value,
+    ) {
+        let value: Value = (ValueKind::String, format, ConfigValue(value))
+            .try_into()
+            .unwrap();
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn test_unknown_value_format() {
+        let property: Property = serde_json::from_value(serde_json::json!({
+            "name": "P1",
+            "property_id": "p1",
+            "type": "STRING",
+            "tags": null,
+            "format": "XML",
+            "value": "<a>1</a>",
+            "segment_rules": []
+        }))
+        .unwrap();
+        assert_eq!(property.format, Some(ValueFormat::Unknown));
+
+        let value: Value = (property.kind, property.format, property.value)
+            .try_into()
+            .unwrap();
+        assert_eq!(value, Value::String("<a>1</a>".into()));
+    }
+
+    #[rstest]
+    #[case(ValueFormat::Json, "{\"a\": ")]
+    #[case(ValueFormat::Yaml, "a: [1, 2")]
+    fn test_string_value_formats_invalid(#[case] format: ValueFormat, #[case] value: &str) {
+        let result: Result<Value, _> = (
+            ValueKind::String,
+            Some(format),
+            ConfigValue(serde_json::json!(value)),
+        )
+            .try_into();
+        let crate::Error::DeserializationError(e) = result.unwrap_err() else {
+            panic!("Error type mismatch!");
+        };
+        assert_eq!(e.string, value);
+    }
 }
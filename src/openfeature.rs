@@ -0,0 +1,272 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [OpenFeature](https://openfeature.dev) provider backed by an [`AppConfigurationClient`].
+
+use std::collections::HashMap;
+
+use open_feature::provider::{FeatureProvider, ProviderMetadata, ResolutionDetails};
+use open_feature::{
+    EvaluationContext, EvaluationContextFieldValue, EvaluationError, EvaluationErrorCode,
+    EvaluationResult, FlagMetadata, StructValue,
+};
+
+use crate::errors::{ConfigurationAccessError, Error};
+use crate::{AppConfigurationClient, Entity, EvaluationDetails, EvaluationReason, Value};
+
+/// An OpenFeature [`FeatureProvider`] resolving flags with an [`AppConfigurationClient`].
+///
+/// The flag key is looked up as a feature first and, if there is no feature with that id,
+/// as a property. The evaluation context is the entity: its targeting key is the entity id
+/// and its custom fields are the entity attributes.
+///
+/// ```
+/// # use appconfiguration::{AppConfigurationClient, AppConfigurationProvider};
+/// # async fn doctest_provider(client: impl AppConfigurationClient + Send + Sync + 'static) {
+/// let mut api = open_feature::OpenFeature::singleton_mut().await;
+/// api.set_provider(AppConfigurationProvider::new(client)).await;
+/// # }
+/// ```
+pub struct AppConfigurationProvider<C> {
+    client: C,
+    metadata: ProviderMetadata,
+}
+
+impl<C: AppConfigurationClient> AppConfigurationProvider<C> {
+    /// Creates a provider resolving flags with the given `client`.
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            metadata: ProviderMetadata::new("IBM Cloud App Configuration"),
+        }
+    }
+
+    /// Returns the client used to resolve flags.
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+
+    fn evaluate(
+        &self,
+        flag_key: &str,
+        evaluation_context: &EvaluationContext,
+    ) -> EvaluationResult<EvaluationDetails> {
+        let entity = ContextEntity::try_from(evaluation_context)?;
+        let details = match self.client.get_feature(flag_key) {
+            Ok(feature) => feature.evaluate_details(&entity),
+            Err(Error::ConfigurationAccessError(ConfigurationAccessError::FeatureNotFound {
+                ..
+            })) => self
+                .client
+                .get_property(flag_key)
+                .and_then(|property| property.evaluate_details(&entity)),
+            Err(e) => Err(e),
+        };
+        details.map_err(to_evaluation_error)
+    }
+
+    fn resolve<T>(
+        &self,
+        flag_key: &str,
+        evaluation_context: &EvaluationContext,
+        convert: impl FnOnce(Value) -> Option<T>,
+    ) -> EvaluationResult<ResolutionDetails<T>> {
+        let details = self.evaluate(flag_key, evaluation_context)?;
+        let flag_metadata = flag_metadata(&details);
+        let reason = to_evaluation_reason(details.reason);
+        let value = convert(details.value).ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::TypeMismatch)
+                .message(format!(
+                    "Value of '{flag_key}' cannot be converted to requested type"
+                ))
+                .build()
+        })?;
+        Ok(ResolutionDetails {
+            value,
+            variant: None,
+            reason: Some(reason),
+            flag_metadata: Some(flag_metadata),
+        })
+    }
+}
+
+#[open_feature::async_trait]
+impl<C: AppConfigurationClient + Send + Sync + 'static> FeatureProvider
+    for AppConfigurationProvider<C>
+{
+    fn metadata(&self) -> &ProviderMetadata {
+        &self.metadata
+    }
+
+    async fn resolve_bool_value(
+        &self,
+        flag_key: &str,
+        evaluation_context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<bool>> {
+        self.resolve(flag_key, evaluation_context, |value| match value {
+            Value::Boolean(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    async fn resolve_int_value(
+        &self,
+        flag_key: &str,
+        evaluation_context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<i64>> {
+        self.resolve(flag_key, evaluation_context, |value| match value {
+            Value::Int64(v) => Some(v),
+            Value::UInt64(v) => i64::try_from(v).ok(),
+            _ => None,
+        })
+    }
+
+    async fn resolve_float_value(
+        &self,
+        flag_key: &str,
+        evaluation_context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<f64>> {
+        self.resolve(flag_key, evaluation_context, |value| match value {
+            Value::Float64(v) => Some(v),
+            Value::Int64(v) => Some(v as f64),
+            Value::UInt64(v) => Some(v as f64),
+            _ => None,
+        })
+    }
+
+    async fn resolve_string_value(
+        &self,
+        flag_key: &str,
+        evaluation_context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<String>> {
+        self.resolve(flag_key, evaluation_context, |value| match value {
+            Value::String(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    async fn resolve_struct_value(
+        &self,
+        flag_key: &str,
+        evaluation_context: &EvaluationContext,
+    ) -> EvaluationResult<ResolutionDetails<StructValue>> {
+        self.resolve(flag_key, evaluation_context, |value| match value {
+            Value::Structured(v @ serde_json::Value::Object(_)) => {
+                match open_feature::Value::try_from(v) {
+                    Ok(open_feature::Value::Struct(v)) => Some(v),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+    }
+}
+
+/// The [`Entity`] described by an OpenFeature evaluation context.
+struct ContextEntity {
+    id: String,
+    attributes: HashMap<String, Value>,
+}
+
+impl Entity for ContextEntity {
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn get_attributes(&self) -> HashMap<String, Value> {
+        self.attributes.clone()
+    }
+}
+
+impl TryFrom<&EvaluationContext> for ContextEntity {
+    type Error = EvaluationError;
+
+    fn try_from(context: &EvaluationContext) -> EvaluationResult<Self> {
+        let id = context.targeting_key.clone().ok_or_else(|| {
+            EvaluationError::builder()
+                .code(EvaluationErrorCode::TargetingKeyMissing)
+                .build()
+        })?;
+        let attributes = context
+            .custom_fields
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), to_attribute_value(name, value)?)))
+            .collect::<EvaluationResult<_>>()?;
+        Ok(Self { id, attributes })
+    }
+}
+
+fn to_attribute_value(name: &str, value: &EvaluationContextFieldValue) -> EvaluationResult<Value> {
+    let invalid_context = |message: String| {
+        EvaluationError::builder()
+            .code(EvaluationErrorCode::InvalidContext)
+            .message(message)
+            .build()
+    };
+    match value {
+        EvaluationContextFieldValue::Bool(v) => Ok(Value::Boolean(*v)),
+        EvaluationContextFieldValue::Int(v) => Ok(Value::Int64(*v)),
+        EvaluationContextFieldValue::Float(v) => Ok(Value::Float64(*v)),
+        EvaluationContextFieldValue::String(v) => Ok(Value::String(v.clone())),
+        // Timestamps are compared as RFC 3339 strings by the segment operators.
+        EvaluationContextFieldValue::DateTime(v) => v
+            .format(&time::format_description::well_known::Rfc3339)
+            .map(Value::String)
+            .map_err(|e| invalid_context(format!("Field '{name}' cannot be formatted: {e}"))),
+        EvaluationContextFieldValue::Struct(_) => Err(invalid_context(format!(
+            "Field '{name}' is a struct, which cannot be used as an attribute"
+        ))),
+    }
+}
+
+fn to_evaluation_reason(reason: EvaluationReason) -> open_feature::EvaluationReason {
+    match reason {
+        EvaluationReason::Disabled => open_feature::EvaluationReason::Disabled,
+        EvaluationReason::TargetingMatch => open_feature::EvaluationReason::TargetingMatch,
+        EvaluationReason::RolloutExcluded => open_feature::EvaluationReason::Split,
+        EvaluationReason::Default => open_feature::EvaluationReason::Default,
+    }
+}
+
+fn flag_metadata(details: &EvaluationDetails) -> FlagMetadata {
+    let mut metadata = FlagMetadata::default();
+    if let Some(rule_order) = details.rule_order {
+        metadata.add_value("rule_order", rule_order);
+    }
+    if let Some(segment_id) = &details.segment_id {
+        metadata.add_value("segment_id", segment_id.as_str());
+    }
+    if let Some(rollout_percentage) = details.rollout_percentage {
+        metadata.add_value("rollout_percentage", rollout_percentage);
+    }
+    metadata
+}
+
+fn to_evaluation_error(error: Error) -> EvaluationError {
+    let code = match &error {
+        Error::ConfigurationAccessError(
+            ConfigurationAccessError::FeatureNotFound { .. }
+            | ConfigurationAccessError::PropertyNotFound { .. },
+        ) => EvaluationErrorCode::FlagNotFound,
+        Error::MismatchType => EvaluationErrorCode::TypeMismatch,
+        Error::DeserializationError(_) => EvaluationErrorCode::ParseError,
+        Error::ClientNotConfigured => EvaluationErrorCode::ProviderNotReady,
+        _ => EvaluationErrorCode::General(error.to_string()),
+    };
+    EvaluationError::builder()
+        .code(code)
+        .message(error.to_string())
+        .build()
+}
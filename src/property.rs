@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use serde::de::DeserializeOwned;
+
 use crate::errors::Result;
 use crate::{Entity, Value};
 
@@ -21,11 +23,11 @@ pub trait Property {
     fn get_name(&self) -> Result<String>;
 
     /// Evaluates a property for the given [`Entity`] and returns a [`Value`].
-    /// 
+    ///
     /// Use the methods available in [`Value`] to return the actual primitive value. If
     /// all you want is the primitive value, you can use the method
     /// [`get_value_into`](Property::get_value_into) instead.
-    /// 
+    ///
     /// # Examples
     ///
     /// ```
@@ -33,13 +35,14 @@ pub trait Property {
     /// # fn doctest_get_value(client: impl AppConfigurationClient, entity: &impl Entity) -> Result<()> {
     ///     let property = client.get_property("my_property")?;
     ///     let value: Value = property.get_value(entity)?;
-    /// 
+    ///
     ///     match value {
     ///         Value::Float64(v) => println!("f64 with value {v}"),
     ///         Value::UInt64(v) => println!("u64 with value {v}"),
     ///         Value::Int64(v) => println!("i64 with value {v}"),
     ///         Value::String(v) => println!("String with value {v}"),
     ///         Value::Boolean(v) => println!("bool with value {v}"),
+    ///         Value::Structured(v) => println!("JSON or YAML data {v}"),
     ///     }
     /// #   Ok(())
     /// # }
@@ -48,7 +51,11 @@ pub trait Property {
 
     /// Evaluates a property for the given [`Entity`] and returns its value converted (if possible)
     /// to the given type.
-    /// 
+    ///
+    /// The conversion uses [`TryFrom<Value>`](Value), implemented for the primitive types and,
+    /// for `JSON` and `YAML` values, [`serde_json::Value`]. Use
+    /// [`get_value_as`](Property::get_value_as) to deserialize into any [`serde::Deserialize`] type.
+    ///
     /// # Examples
     ///
     /// ```
@@ -56,7 +63,7 @@ pub trait Property {
     /// # fn doctest_get_value_into(client: impl AppConfigurationClient, entity: &impl Entity) -> Result<()> {
     ///     let property = client.get_property("my_bool_feature")?;
     ///     let value: bool = property.get_value_into(entity)?;
-    /// 
+    ///
     ///     // an bool cannot be returned as something else
     ///     assert!(property.get_value_into::<f64>(entity).is_err());
     ///     assert!(property.get_value_into::<String>(entity).is_err());
@@ -68,4 +75,29 @@ pub trait Property {
         &self,
         entity: &impl Entity,
     ) -> Result<T>;
+
+    /// Evaluates a property for the given [`Entity`] and deserializes its value into `T`.
+    ///
+    /// Any type implementing [`serde::Deserialize`] can be used, which is convenient for
+    /// values with `JSON` or `YAML` format. If the value cannot be deserialized, an
+    /// [`Error::DeserializationError`](crate::Error::DeserializationError) is returned
+    /// containing the evaluated value as a string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use appconfiguration::{AppConfigurationClient, Property, Result, Entity};
+    /// # fn doctest_get_value_as(client: impl AppConfigurationClient, entity: &impl Entity) -> Result<()> {
+    ///     #[derive(serde::Deserialize)]
+    ///     struct PoolSettings {
+    ///         size: u32,
+    ///         timeout_secs: Option<u64>,
+    ///     }
+    ///
+    ///     let property = client.get_property("my_pool_property")?;
+    ///     let settings: PoolSettings = property.get_value_as(entity)?;
+    /// #   Ok(())
+    /// # }
+    /// ```
+    fn get_value_as<T: DeserializeOwned>(&self, entity: &impl Entity) -> Result<T>;
 }
@@ -26,7 +26,13 @@ pub(crate) enum SegmentEvaluationError {
 }
 
 #[derive(Debug, Error)]
-#[error("Operation '{}' '{}' '{}' failed to evaluate: {}", segment_rule_attribute_name, segment_rule_operator,  value, source)]
+#[error(
+    "Operation '{}' '{}' '{}' failed to evaluate: {}",
+    segment_rule_attribute_name,
+    segment_rule_operator,
+    value,
+    source
+)]
 pub(crate) struct SegmentEvaluationErrorKind {
     pub(crate) segment_id: String,
     pub(crate) segment_rule_attribute_name: String,
@@ -65,6 +71,45 @@ pub(crate) enum CheckOperatorErrorDetail {
     #[error("Entity attribute is not a number.")]
     EntityAttrNotANumber,
 
+    #[error("Entity attribute with structured data cannot be compared.")]
+    StructuredValueNotSupported,
+
+    #[error("Entity attribute is not a semantic version: {0}")]
+    EntityAttrNotAVersion(semver::Error),
+
+    #[error("Segment rule value is not a semantic version: {0}")]
+    ValueNotAVersion(semver::Error),
+
+    #[error("Entity attribute is not an RFC 3339 timestamp: {0}")]
+    EntityAttrNotATimestamp(chrono::ParseError),
+
+    #[error("Segment rule value is not an RFC 3339 timestamp: {0}")]
+    ValueNotATimestamp(chrono::ParseError),
+
+    #[error("Segment rule value is not a valid regular expression: {0}")]
+    InvalidPattern(regex::Error),
+
     #[error("Operator not implemented.")]
     OperatorNotImplemented,
 }
+
+impl CheckOperatorErrorDetail {
+    /// Short name of the error, used as a label in the metrics.
+    #[cfg(feature = "metrics")]
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::StringExpected => "string_expected",
+            Self::BooleanExpected(_) => "boolean_expected",
+            Self::FloatExpected(_) => "float_expected",
+            Self::IntegerExpected(_) => "integer_expected",
+            Self::EntityAttrNotANumber => "entity_attr_not_a_number",
+            Self::StructuredValueNotSupported => "structured_value_not_supported",
+            Self::EntityAttrNotAVersion(_) => "entity_attr_not_a_version",
+            Self::ValueNotAVersion(_) => "value_not_a_version",
+            Self::EntityAttrNotATimestamp(_) => "entity_attr_not_a_timestamp",
+            Self::ValueNotATimestamp(_) => "value_not_a_timestamp",
+            Self::InvalidPattern(_) => "invalid_pattern",
+            Self::OperatorNotImplemented => "operator_not_implemented",
+        }
+    }
+}
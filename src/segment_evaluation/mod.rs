@@ -14,52 +14,60 @@
 
 pub(crate) mod errors;
 
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 
-use crate::entity::{Entity};
+use crate::entity::Entity;
 use crate::errors::Result;
-use crate::models::Segment;
 use crate::models::TargetingRule;
+use crate::models::{Segment, SegmentRule};
 use crate::Value;
+use chrono::{DateTime, Utc};
 use errors::{CheckOperatorErrorDetail, SegmentEvaluationError};
+use regex::Regex;
 
+/// Returns the first targeting rule (by `order`) applying to the entity, together
+/// with the id of the segment that matched.
 pub(crate) fn find_applicable_segment_rule_for_entity(
     segments: &HashMap<String, Segment>,
     segment_rules: impl Iterator<Item = TargetingRule>,
     entity: &impl Entity,
-) -> Result<Option<TargetingRule>> {
+) -> Result<Option<(TargetingRule, String)>> {
     let mut targeting_rules = segment_rules.collect::<Vec<_>>();
-    targeting_rules.sort_by(|a, b| a.order.cmp(&b.order));
+    targeting_rules.sort_by_key(|rule| rule.order);
 
     for targeting_rule in targeting_rules.into_iter() {
-        if targeting_rule_applies_to_entity(segments, &targeting_rule, entity)? {
-            return Ok(Some(targeting_rule));
+        if let Some(segment_id) =
+            targeting_rule_applies_to_entity(segments, &targeting_rule, entity)?
+        {
+            let segment_id = segment_id.clone();
+            return Ok(Some((targeting_rule, segment_id)));
         }
     }
     Ok(None)
 }
 
-fn targeting_rule_applies_to_entity(
+fn targeting_rule_applies_to_entity<'a>(
     segments: &HashMap<String, Segment>,
-    targeting_rule: &TargetingRule,
+    targeting_rule: &'a TargetingRule,
     entity: &impl Entity,
-) -> std::result::Result<bool, SegmentEvaluationError> {
+) -> std::result::Result<Option<&'a String>, SegmentEvaluationError> {
     // TODO: we need to get the naming correct here to distinguish between rules, segments, segment_ids, targeting_rules etc. correctly
     let rules = &targeting_rule.rules;
     for rule in rules.iter() {
-        let rule_applies = segment_applies_to_entity(segments, &rule.segments, entity)?;
-        if rule_applies {
-            return Ok(true);
+        let segment_id = segment_applies_to_entity(segments, &rule.segments, entity)?;
+        if segment_id.is_some() {
+            return Ok(segment_id);
         }
     }
-    Ok(false)
+    Ok(None)
 }
 
-fn segment_applies_to_entity(
+fn segment_applies_to_entity<'a>(
     segments: &HashMap<String, Segment>,
-    segment_ids: &[String],
+    segment_ids: &'a [String],
     entity: &impl Entity,
-) -> std::result::Result<bool, SegmentEvaluationError> {
+) -> std::result::Result<Option<&'a String>, SegmentEvaluationError> {
     for segment_id in segment_ids.iter() {
         let segment = segments
             .get(segment_id)
@@ -68,10 +76,10 @@ fn segment_applies_to_entity(
             ))?;
         let applies = belong_to_segment(segment, entity.get_attributes())?;
         if applies {
-            return Ok(true);
+            return Ok(Some(segment_id));
         }
     }
-    Ok(false)
+    Ok(None)
 }
 
 fn belong_to_segment(
@@ -82,29 +90,40 @@ fn belong_to_segment(
         let operator = &rule.operator;
         let attr_name = &rule.attribute_name;
         let attr_value = attrs.get(attr_name);
-        if attr_value.is_none() {
-            return Ok(false);
-        }
         let rule_result = match attr_value {
             None => {
-                println!("Warning: Operation '{attr_name}' '{operator}' '[...]' failed to evaluate: '{attr_name}' not found in entity");
+                tracing::debug!(
+                    segment_id = %segment.segment_id,
+                    attribute = %attr_name,
+                    operator = %operator,
+                    "Attribute not found in entity, segment does not apply"
+                );
                 false
             }
+            Some(Value::String(data)) if rule.compiled_values.is_some() => {
+                compiled_rule_applies(segment, rule, data)?
+            }
             Some(attr_value) => {
                 // FIXME: the following algorithm is too hard to read. Is it just me or do we need to simplify this?
                 // One of the values needs to match.
                 // Find a candidate (a candidate corresponds to a value which matches or which might match but the operator failed):
+                // Negated operators are the exception: they need to hold for all the values
+                // (e.g. `notContains` matches if the attribute contains none of the values),
+                // so we look for a value for which they don't hold instead.
+                let negated = is_negated_operator(operator);
                 let candidate = rule
                     .values
                     .iter()
                     .find_map(|value| match check_operator(attr_value, operator, value) {
-                        Ok(true) => Some(Ok::<_, SegmentEvaluationError>(())),
-                        Ok(false) => None,
+                        Ok(result) if result != negated => {
+                            Some(Ok::<_, SegmentEvaluationError>(()))
+                        }
+                        Ok(_) => None,
                         Err(e) => Some(Err((e, segment, rule, value).into())),
                     })
                     .transpose()?;
                 // check if the candidate is good, or if the operator failed:
-                candidate.is_some()
+                candidate.is_some() != negated
             }
         };
         // All rules must match:
@@ -115,6 +134,34 @@ fn belong_to_segment(
     Ok(true)
 }
 
+/// Evaluates a rule using its precompiled values instead of checking the operator
+/// against every value.
+fn compiled_rule_applies(
+    segment: &Segment,
+    rule: &SegmentRule,
+    attribute_value: &str,
+) -> std::result::Result<bool, SegmentEvaluationError> {
+    match rule.compiled_values.as_deref() {
+        Some(CompiledValues::Set(values)) => {
+            Ok(values.contains(attribute_value) != is_negated_operator(&rule.operator))
+        }
+        Some(CompiledValues::Patterns(patterns)) => {
+            for (pattern, value) in patterns.iter().zip(rule.values.iter()) {
+                match pattern {
+                    Ok(regex) if regex.is_match(attribute_value) => return Ok(true),
+                    Ok(_) => {}
+                    Err(e) => {
+                        let e = CheckOperatorErrorDetail::InvalidPattern(e.clone());
+                        return Err((e, segment, rule, value).into());
+                    }
+                }
+            }
+            Ok(false)
+        }
+        None => Ok(false),
+    }
+}
+
 fn check_operator(
     attribute_value: &Value,
     operator: &str,
@@ -127,6 +174,7 @@ fn check_operator(
             Value::Float64(data) => Ok(*data == reference_value.parse::<f64>()?),
             Value::UInt64(data) => Ok(*data == reference_value.parse::<u64>()?),
             Value::Int64(data) => Ok(*data == reference_value.parse::<i64>()?),
+            Value::Structured(_) => Err(CheckOperatorErrorDetail::StructuredValueNotSupported),
         },
         "contains" => match attribute_value {
             Value::String(data) => Ok(data.contains(reference_value)),
@@ -167,10 +215,97 @@ fn check_operator(
             Value::Int64(data) => Ok(*data <= reference_value.parse()?),
             _ => Err(CheckOperatorErrorDetail::EntityAttrNotANumber),
         },
+        "semverGreaterThan" => Ok(compare_versions(attribute_value, reference_value)?.is_gt()),
+        "semverLesserThan" => Ok(compare_versions(attribute_value, reference_value)?.is_lt()),
+        "semverGreaterThanEquals" => {
+            Ok(compare_versions(attribute_value, reference_value)?.is_ge())
+        }
+        "semverLesserThanEquals" => Ok(compare_versions(attribute_value, reference_value)?.is_le()),
+        "before" => Ok(compare_timestamps(attribute_value, reference_value)?.is_lt()),
+        "after" => Ok(compare_timestamps(attribute_value, reference_value)?.is_gt()),
+        "matches" => match attribute_value {
+            Value::String(data) => Ok(Regex::new(reference_value)
+                .map_err(CheckOperatorErrorDetail::InvalidPattern)?
+                .is_match(data)),
+            _ => Err(CheckOperatorErrorDetail::StringExpected),
+        },
+        "in" => check_operator(attribute_value, "is", reference_value),
+        "notIn" => check_operator(attribute_value, "is", reference_value).map(|r| !r),
+        "isNot" => check_operator(attribute_value, "is", reference_value).map(|r| !r),
+        "notContains" => check_operator(attribute_value, "contains", reference_value).map(|r| !r),
+        "notStartsWith" => {
+            check_operator(attribute_value, "startsWith", reference_value).map(|r| !r)
+        }
+        "notEndsWith" => check_operator(attribute_value, "endsWith", reference_value).map(|r| !r),
         _ => Err(CheckOperatorErrorDetail::OperatorNotImplemented),
     }
 }
 
+/// Compares two semantic versions (`MAJOR.MINOR.PATCH[-PRERELEASE][+BUILD]`) following
+/// semver precedence rules: pre-releases go before the release, build metadata is ignored.
+fn compare_versions(
+    attribute_value: &Value,
+    reference_value: &str,
+) -> std::result::Result<Ordering, CheckOperatorErrorDetail> {
+    let Value::String(data) = attribute_value else {
+        return Err(CheckOperatorErrorDetail::StringExpected);
+    };
+    let version =
+        semver::Version::parse(data).map_err(CheckOperatorErrorDetail::EntityAttrNotAVersion)?;
+    let reference = semver::Version::parse(reference_value)
+        .map_err(CheckOperatorErrorDetail::ValueNotAVersion)?;
+    Ok(version.cmp_precedence(&reference))
+}
+
+/// Compares two RFC 3339 timestamps (e.g. `2025-01-01T00:00:00Z`). The reference value
+/// `$now` stands for the time of the evaluation.
+fn compare_timestamps(
+    attribute_value: &Value,
+    reference_value: &str,
+) -> std::result::Result<Ordering, CheckOperatorErrorDetail> {
+    let Value::String(data) = attribute_value else {
+        return Err(CheckOperatorErrorDetail::StringExpected);
+    };
+    let timestamp = DateTime::parse_from_rfc3339(data)
+        .map_err(CheckOperatorErrorDetail::EntityAttrNotATimestamp)?;
+    let reference = if reference_value == "$now" {
+        Utc::now().fixed_offset()
+    } else {
+        DateTime::parse_from_rfc3339(reference_value)
+            .map_err(CheckOperatorErrorDetail::ValueNotATimestamp)?
+    };
+    Ok(timestamp.cmp(&reference))
+}
+
+/// Values of a [`SegmentRule`] prepared once per configuration snapshot, so they are
+/// not processed again on every evaluation.
+#[derive(Debug)]
+pub(crate) enum CompiledValues {
+    /// Regular expressions for the `matches` operator (or the error compiling them).
+    Patterns(Vec<std::result::Result<Regex, regex::Error>>),
+    /// Values for the `in` and `notIn` operators.
+    Set(HashSet<String>),
+}
+
+impl CompiledValues {
+    pub(crate) fn new(operator: &str, values: &[String]) -> Option<Self> {
+        match operator {
+            "matches" => Some(Self::Patterns(
+                values.iter().map(|value| Regex::new(value)).collect(),
+            )),
+            "in" | "notIn" => Some(Self::Set(values.iter().cloned().collect())),
+            _ => None,
+        }
+    }
+}
+
+fn is_negated_operator(operator: &str) -> bool {
+    matches!(
+        operator,
+        "isNot" | "notContains" | "notStartsWith" | "notEndsWith" | "notIn"
+    )
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -191,6 +326,7 @@ pub mod tests {
                     attribute_name: "name".into(),
                     operator: "is".into(),
                     values: vec!["heinz".into()],
+                    compiled_values: None,
                 }],
             },
         )])
@@ -220,12 +356,17 @@ pub mod tests {
             id: "a2".into(),
             attributes: HashMap::from([("name2".into(), Value::from("heinz".to_string()))]),
         };
-        let rule =
-            find_applicable_segment_rule_for_entity(&segments, segment_rules.into_iter(), &entity);
+        let (rule, events) = crate::tests::event_capture::capture_events(|| {
+            find_applicable_segment_rule_for_entity(&segments, segment_rules.into_iter(), &entity)
+        });
         // Segment evaluation should not fail:
         let rule = rule.unwrap();
         // But no segment should be found:
-        assert!(rule.is_none())
+        assert!(rule.is_none());
+        assert_eq!(
+            events,
+            vec!["DEBUG Attribute not found in entity, segment does not apply segment_id=some_segment_id_1 attribute=name operator=is"]
+        );
     }
 
     // SCENARIO - The segment_id present in featureflag is invalid. In other words - the /config json dump has a featureflag, which has segment_rules. The segment_id in this segment_rules is invalid. Because this segment_id is not found in segments array.
@@ -284,4 +425,268 @@ pub mod tests {
         assert_eq!(error.segment_rule_attribute_name, "name");
         assert_eq!(error.value, "heinz");
     }
+
+    #[rstest]
+    #[case(Value::from("heinz".to_string()), "isNot", "heinz", false)]
+    #[case(Value::from("heinz".to_string()), "isNot", "karl", true)]
+    #[case(Value::from(true), "isNot", "false", true)]
+    #[case(Value::from(42.0), "isNot", "42", false)]
+    #[case(Value::from(42u64), "isNot", "41", true)]
+    #[case(Value::from(-42i64), "isNot", "-42", false)]
+    #[case(Value::from("heinz".to_string()), "notContains", "ein", false)]
+    #[case(Value::from("heinz".to_string()), "notContains", "karl", true)]
+    #[case(Value::from("heinz".to_string()), "notStartsWith", "hei", false)]
+    #[case(Value::from("heinz".to_string()), "notStartsWith", "einz", true)]
+    #[case(Value::from("heinz".to_string()), "notEndsWith", "einz", false)]
+    #[case(Value::from("heinz".to_string()), "notEndsWith", "hei", true)]
+    fn test_negated_operators(
+        #[case] attribute_value: Value,
+        #[case] operator: &str,
+        #[case] reference_value: &str,
+        #[case] expected: bool,
+    ) {
+        let result = check_operator(&attribute_value, operator, reference_value).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case(Value::from(42.0), "notContains")]
+    #[case(Value::from(true), "notStartsWith")]
+    #[case(Value::from(42i64), "notEndsWith")]
+    fn test_negated_operators_string_expected(
+        #[case] attribute_value: Value,
+        #[case] operator: &str,
+    ) {
+        let result = check_operator(&attribute_value, operator, "42");
+        assert!(matches!(
+            result,
+            Err(CheckOperatorErrorDetail::StringExpected)
+        ));
+    }
+
+    #[test]
+    fn test_negated_operators_type_mismatch() {
+        let result = check_operator(&Value::from(true), "isNot", "heinz");
+        assert!(matches!(
+            result,
+            Err(CheckOperatorErrorDetail::BooleanExpected(_))
+        ));
+    }
+
+    // Negated operators need to hold for all the values in the segment rule.
+    #[rstest]
+    #[case("heinz", false)]
+    #[case("karl", false)]
+    #[case("otto", true)]
+    fn test_negated_operator_with_several_values(#[case] name: &str, #[case] expected: bool) {
+        let segment = Segment {
+            _name: "".into(),
+            segment_id: "some_segment_id_1".into(),
+            _description: "".into(),
+            _tags: None,
+            rules: vec![SegmentRule {
+                attribute_name: "name".into(),
+                operator: "isNot".into(),
+                values: vec!["heinz".into(), "karl".into()],
+                compiled_values: None,
+            }],
+        };
+        let attributes = HashMap::from([("name".into(), Value::from(name.to_string()))]);
+        let result = belong_to_segment(&segment, attributes).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    #[case("1.10.0", "semverGreaterThan", "1.9.3", true)]
+    #[case("1.9.3", "semverGreaterThan", "1.10.0", false)]
+    #[case("1.10.0", "semverGreaterThan", "1.10.0", false)]
+    #[case("1.10.0", "semverGreaterThanEquals", "1.10.0", true)]
+    #[case("1.9.3", "semverLesserThan", "1.10.0", true)]
+    #[case("1.10.0", "semverLesserThan", "1.10.0", false)]
+    #[case("1.10.0", "semverLesserThanEquals", "1.10.0", true)]
+    #[case("2.0.0", "semverLesserThanEquals", "1.10.0", false)]
+    // pre-releases go before the release, and are ordered by their identifiers
+    #[case("1.0.0-rc.1", "semverLesserThan", "1.0.0", true)]
+    #[case("1.0.0-alpha", "semverLesserThan", "1.0.0-alpha.1", true)]
+    #[case("1.0.0-alpha.10", "semverGreaterThan", "1.0.0-alpha.9", true)]
+    #[case("1.0.0-beta", "semverGreaterThan", "1.0.0-alpha.beta", true)]
+    // build metadata is ignored
+    #[case("1.0.0+build.2", "semverGreaterThan", "1.0.0+build.1", false)]
+    #[case("1.0.0+build.2", "semverLesserThanEquals", "1.0.0", true)]
+    fn test_semver_operators(
+        #[case] version: &str,
+        #[case] operator: &str,
+        #[case] reference_value: &str,
+        #[case] expected: bool,
+    ) {
+        let attribute_value = Value::from(version.to_string());
+        let result = check_operator(&attribute_value, operator, reference_value).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_semver_operators_errors() {
+        let result = check_operator(
+            &Value::from("1.10".to_string()),
+            "semverGreaterThan",
+            "1.9.3",
+        );
+        assert!(matches!(
+            result,
+            Err(CheckOperatorErrorDetail::EntityAttrNotAVersion(_))
+        ));
+
+        let result = check_operator(
+            &Value::from("1.10.0".to_string()),
+            "semverGreaterThan",
+            "v1.9.3",
+        );
+        assert!(matches!(
+            result,
+            Err(CheckOperatorErrorDetail::ValueNotAVersion(_))
+        ));
+
+        let result = check_operator(&Value::from(1.10), "semverGreaterThan", "1.9.3");
+        assert!(matches!(
+            result,
+            Err(CheckOperatorErrorDetail::StringExpected)
+        ));
+    }
+
+    #[rstest]
+    #[case("2024-12-31T23:59:59Z", "before", "2025-01-01T00:00:00Z", true)]
+    #[case("2025-01-01T00:00:00Z", "before", "2025-01-01T00:00:00Z", false)]
+    #[case("2025-01-01T00:00:01Z", "after", "2025-01-01T00:00:00Z", true)]
+    #[case("2025-01-01T00:00:00Z", "after", "2025-01-01T00:00:00Z", false)]
+    // offsets are taken into account
+    #[case("2025-01-01T00:30:00+01:00", "before", "2025-01-01T00:00:00Z", true)]
+    #[case("2024-12-31T23:30:00-01:00", "after", "2025-01-01T00:00:00Z", true)]
+    #[case("2000-01-01T00:00:00Z", "before", "$now", true)]
+    #[case("9999-01-01T00:00:00Z", "after", "$now", true)]
+    fn test_timestamp_operators(
+        #[case] timestamp: &str,
+        #[case] operator: &str,
+        #[case] reference_value: &str,
+        #[case] expected: bool,
+    ) {
+        let attribute_value = Value::from(timestamp.to_string());
+        let result = check_operator(&attribute_value, operator, reference_value).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_timestamp_operators_errors() {
+        let result = check_operator(
+            &Value::from("2025-01-01".to_string()),
+            "before",
+            "2025-01-01T00:00:00Z",
+        );
+        assert!(matches!(
+            result,
+            Err(CheckOperatorErrorDetail::EntityAttrNotATimestamp(_))
+        ));
+
+        let result = check_operator(
+            &Value::from("2025-01-01T00:00:00Z".to_string()),
+            "after",
+            "now",
+        );
+        assert!(matches!(
+            result,
+            Err(CheckOperatorErrorDetail::ValueNotATimestamp(_))
+        ));
+
+        let result = check_operator(&Value::from(1735689600u64), "after", "2025-01-01T00:00:00Z");
+        assert!(matches!(
+            result,
+            Err(CheckOperatorErrorDetail::StringExpected)
+        ));
+    }
+
+    #[rstest]
+    #[case("heinz@ibm.com", "matches", r"^[a-z]+@ibm\.com$", true)]
+    #[case("heinz@ibm.co", "matches", r"^[a-z]+@ibm\.com$", false)]
+    #[case("heinz", "in", "heinz", true)]
+    #[case("heinz", "in", "karl", false)]
+    #[case("heinz", "notIn", "heinz", false)]
+    #[case("heinz", "notIn", "karl", true)]
+    fn test_pattern_and_membership_operators(
+        #[case] attribute_value: &str,
+        #[case] operator: &str,
+        #[case] reference_value: &str,
+        #[case] expected: bool,
+    ) {
+        let attribute_value = Value::from(attribute_value.to_string());
+        let result = check_operator(&attribute_value, operator, reference_value).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    fn segment_with_rule(operator: &str, values: &[&str], precompile: bool) -> Segment {
+        let mut rule = SegmentRule {
+            attribute_name: "tenant".into(),
+            operator: operator.into(),
+            values: values.iter().map(|v| v.to_string()).collect(),
+            compiled_values: None,
+        };
+        if precompile {
+            rule.precompile();
+        }
+        Segment {
+            _name: "".into(),
+            segment_id: "some_segment_id_1".into(),
+            _description: "".into(),
+            _tags: None,
+            rules: vec![rule],
+        }
+    }
+
+    // Precompiled rules must give the same results as the ones evaluated value by value.
+    #[rstest]
+    #[case("in", "t2", true)]
+    #[case("in", "t4", false)]
+    #[case("notIn", "t2", false)]
+    #[case("notIn", "t4", true)]
+    #[case("matches", "tenant-42", true)]
+    #[case("matches", "t2x", true)]
+    #[case("matches", "x1", false)]
+    fn test_precompiled_rules(
+        #[case] operator: &str,
+        #[case] tenant: &str,
+        #[case] expected: bool,
+        #[values(true, false)] precompile: bool,
+    ) {
+        let values = match operator {
+            "matches" => vec![r"^tenant-\d+$", "^t[0-9]"],
+            _ => vec!["t1", "t2", "t3"],
+        };
+        let segment = segment_with_rule(operator, &values, precompile);
+        assert_eq!(segment.rules[0].compiled_values.is_some(), precompile);
+        let attributes = HashMap::from([("tenant".into(), Value::from(tenant.to_string()))]);
+        let result = belong_to_segment(&segment, attributes).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[rstest]
+    fn test_invalid_pattern(#[values(true, false)] precompile: bool) {
+        let segment = segment_with_rule("matches", &["^t[0-9]", "(unclosed"], precompile);
+        let attributes = HashMap::from([("tenant".into(), Value::from("x1".to_string()))]);
+        let e = belong_to_segment(&segment, attributes).unwrap_err();
+        let SegmentEvaluationError::SegmentEvaluationFailed(ref error) = e else {
+            panic!("Error type mismatch!");
+        };
+        assert_eq!(error.segment_id, "some_segment_id_1");
+        assert_eq!(error.value, "(unclosed");
+        assert!(matches!(
+            error.source,
+            CheckOperatorErrorDetail::InvalidPattern(_)
+        ));
+    }
+
+    // Non-string attributes are compared value by value, even if the rule is precompiled.
+    #[test]
+    fn test_membership_with_numbers() {
+        let segment = segment_with_rule("in", &["1", "42"], true);
+        let attributes = HashMap::from([("tenant".into(), Value::from(42u64))]);
+        assert!(belong_to_segment(&segment, attributes).unwrap());
+    }
 }
@@ -0,0 +1,90 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Runs `f` and returns the `tracing` events emitted in the current thread, formatted
+/// as `LEVEL message field=value... [span fields]`. Only the fields of the explicit
+/// parent span are included.
+pub(crate) fn capture_events<R>(f: impl FnOnce() -> R) -> (R, Vec<String>) {
+    let subscriber = CaptureSubscriber::default();
+    let events = subscriber.events.clone();
+    let result = tracing::subscriber::with_default(subscriber, f);
+    let events = events.lock().unwrap().clone();
+    (result, events)
+}
+
+#[derive(Default)]
+struct CaptureSubscriber {
+    next_id: AtomicU64,
+    spans: Mutex<HashMap<u64, String>>,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+#[derive(Default)]
+struct FieldsVisitor(String);
+
+impl Visit for FieldsVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.0.insert_str(0, &format!("{value:?}"));
+        } else {
+            self.0.push_str(&format!(" {}={value:?}", field.name()));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_debug(field, &format_args!("{value}"))
+    }
+}
+
+impl Subscriber for CaptureSubscriber {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut visitor = FieldsVisitor::default();
+        span.record(&mut visitor);
+        self.spans.lock().unwrap().insert(id, visitor.0);
+        Id::from_u64(id)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = FieldsVisitor::default();
+        event.record(&mut visitor);
+        let mut line = format!("{} {}", event.metadata().level(), visitor.0);
+        if let Some(parent) = event.parent() {
+            let spans = self.spans.lock().unwrap();
+            line.push_str(&format!(" [{}]", spans[&parent.into_u64()].trim_start()));
+        }
+        self.events.lock().unwrap().push(line);
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
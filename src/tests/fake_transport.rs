@@ -0,0 +1,168 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "async")]
+use crate::client::transport::{AsyncTransport, AsyncUpdateStream};
+use crate::models::Configuration;
+use crate::{Error, Result, ServiceInstance, Transport, UpdateStream};
+
+/// In-memory [`Transport`] serving a configuration that tests can modify.
+#[derive(Clone, Default)]
+pub(crate) struct FakeTransport {
+    configuration: Arc<Mutex<String>>,
+    streams: Arc<Mutex<Vec<StreamSender>>>,
+    offline: Arc<AtomicBool>,
+}
+
+impl FakeTransport {
+    pub(crate) fn new(configuration: &Configuration) -> Self {
+        let transport = Self::default();
+        *transport.configuration.lock().unwrap() = serde_json::to_string(configuration).unwrap();
+        transport
+    }
+
+    /// Replaces the configuration and notifies every open update stream.
+    pub(crate) fn update_configuration(&self, configuration: &Configuration) {
+        *self.configuration.lock().unwrap() = serde_json::to_string(configuration).unwrap();
+        self.streams
+            .lock()
+            .unwrap()
+            .retain(|stream| stream.notify());
+    }
+
+    /// Simulates losing the connection: open update streams are closed and new
+    /// requests fail while offline.
+    pub(crate) fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::SeqCst);
+        if offline {
+            self.streams.lock().unwrap().clear();
+        }
+    }
+
+    fn check_online(&self) -> Result<()> {
+        match self.offline.load(Ordering::SeqCst) {
+            true => Err(Error::Other("Server not reachable".to_string())),
+            false => Ok(()),
+        }
+    }
+}
+
+impl Transport for FakeTransport {
+    type UpdateStream = FakeUpdateStream;
+
+    fn fetch_token(&self, _instance: &ServiceInstance, _apikey: &str) -> Result<String> {
+        self.check_online()?;
+        Ok(r#"{"access_token": "fake-token", "expires_in": 3600}"#.to_string())
+    }
+
+    fn fetch_configuration(
+        &self,
+        _instance: &ServiceInstance,
+        access_token: &str,
+    ) -> Result<String> {
+        self.check_online()?;
+        assert_eq!(access_token, "fake-token");
+        Ok(self.configuration.lock().unwrap().clone())
+    }
+
+    fn open_update_stream(
+        &self,
+        _instance: &ServiceInstance,
+        _access_token: &str,
+    ) -> Result<Self::UpdateStream> {
+        self.check_online()?;
+        let (sender, receiver) = channel();
+        self.streams
+            .lock()
+            .unwrap()
+            .push(StreamSender::Blocking(sender));
+        Ok(FakeUpdateStream(receiver))
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTransport for FakeTransport {
+    type AsyncUpdateStream = FakeAsyncUpdateStream;
+
+    async fn fetch_token_async(&self, instance: &ServiceInstance, apikey: &str) -> Result<String> {
+        self.fetch_token(instance, apikey)
+    }
+
+    async fn fetch_configuration_async(
+        &self,
+        instance: &ServiceInstance,
+        access_token: &str,
+    ) -> Result<String> {
+        self.fetch_configuration(instance, access_token)
+    }
+
+    async fn open_update_stream_async(
+        &self,
+        _instance: &ServiceInstance,
+        _access_token: &str,
+    ) -> Result<Self::AsyncUpdateStream> {
+        self.check_online()?;
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        self.streams
+            .lock()
+            .unwrap()
+            .push(StreamSender::Async(sender));
+        Ok(FakeAsyncUpdateStream(receiver))
+    }
+}
+
+/// Notifies the update streams opened by the sync and async clients.
+enum StreamSender {
+    Blocking(Sender<()>),
+    #[cfg(feature = "async")]
+    Async(tokio::sync::mpsc::UnboundedSender<()>),
+}
+
+impl StreamSender {
+    /// Returns `false` if the stream has been closed.
+    fn notify(&self) -> bool {
+        match self {
+            Self::Blocking(sender) => sender.send(()).is_ok(),
+            #[cfg(feature = "async")]
+            Self::Async(sender) => sender.send(()).is_ok(),
+        }
+    }
+}
+
+pub(crate) struct FakeUpdateStream(Receiver<()>);
+
+impl UpdateStream for FakeUpdateStream {
+    fn wait_for_update(&mut self) -> Result<()> {
+        self.0
+            .recv()
+            .map_err(|_| Error::Other("Connection closed by the server".to_string()))
+    }
+}
+
+#[cfg(feature = "async")]
+pub(crate) struct FakeAsyncUpdateStream(tokio::sync::mpsc::UnboundedReceiver<()>);
+
+#[cfg(feature = "async")]
+impl AsyncUpdateStream for FakeAsyncUpdateStream {
+    async fn wait_for_update(&mut self) -> Result<()> {
+        self.0
+            .recv()
+            .await
+            .ok_or_else(|| Error::Other("Connection closed by the server".to_string()))
+    }
+}
@@ -14,20 +14,40 @@
 
 use std::collections::HashMap;
 
+pub(crate) mod event_capture;
+mod fake_transport;
+#[cfg(feature = "async")]
+mod test_async_client;
+mod test_builder;
+mod test_client_defaults;
+mod test_connection_status;
+mod test_endpoints;
+mod test_evaluated_bootstrap;
 mod test_get_feature;
 mod test_get_feature_ids;
 mod test_get_property;
 mod test_get_property_ids;
+mod test_hooks;
+#[cfg(feature = "metrics")]
+mod test_metrics;
+mod test_offline_client;
+#[cfg(feature = "openfeature")]
+mod test_openfeature;
+mod test_persistence;
+mod test_subscriptions;
+mod test_transport;
 mod test_using_example_data;
+mod test_using_lite_plan_data;
+mod test_value_formats;
 
 use crate::client::cache::ConfigurationSnapshot;
+use crate::client::monitor::ClientState;
 use crate::client::AppConfigurationClientIBMCloud;
-use crate::models::tests::example_configuration_enterprise;
+use crate::models::tests::{example_configuration_enterprise, example_configuration_lite};
 use crate::models::Configuration;
 use crate::Entity;
-use rstest::fixture;
 use crate::Value;
-use std::sync::{Arc, Mutex};
+use rstest::fixture;
 
 pub struct TrivialEntity;
 
@@ -57,7 +77,9 @@ impl Entity for GenericEntity {
 }
 
 #[fixture]
-fn client_enterprise(example_configuration_enterprise: Configuration) -> AppConfigurationClientIBMCloud {
+fn client_enterprise(
+    example_configuration_enterprise: Configuration,
+) -> AppConfigurationClientIBMCloud {
     let configuration_snapshot =
         ConfigurationSnapshot::new("dev", example_configuration_enterprise).unwrap();
 
@@ -65,7 +87,37 @@ fn client_enterprise(example_configuration_enterprise: Configuration) -> AppConf
     let (sender, _) = std::sync::mpsc::channel();
 
     AppConfigurationClientIBMCloud {
-        latest_config_snapshot: Arc::new(Mutex::new(configuration_snapshot)),
+        state: ClientState::new(configuration_snapshot),
         _thread_terminator: sender,
     }
 }
+
+#[fixture]
+fn client_lite(example_configuration_lite: Configuration) -> AppConfigurationClientIBMCloud {
+    let configuration_snapshot =
+        ConfigurationSnapshot::new("dev", example_configuration_lite).unwrap();
+
+    let (sender, _) = std::sync::mpsc::channel();
+
+    AppConfigurationClientIBMCloud {
+        state: ClientState::new(configuration_snapshot),
+        _thread_terminator: sender,
+    }
+}
+
+#[cfg(feature = "async")]
+#[fixture]
+fn client_enterprise_async(
+    example_configuration_enterprise: Configuration,
+) -> crate::AppConfigurationClientIBMCloudAsync {
+    let configuration_snapshot =
+        ConfigurationSnapshot::new("dev", example_configuration_enterprise).unwrap();
+
+    // Create the client
+    let (sender, _) = tokio::sync::oneshot::channel();
+
+    crate::AppConfigurationClientIBMCloudAsync {
+        state: ClientState::new(configuration_snapshot),
+        _task_terminator: sender,
+    }
+}
@@ -0,0 +1,209 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use crate::client::cache::ConfigurationSnapshot;
+use crate::client::AppConfigurationClient;
+use crate::models::tests::{configuration_feature1_enabled, example_configuration_enterprise};
+use crate::models::Configuration;
+use crate::tests::TrivialEntity;
+use crate::AppConfigurationClientIBMCloudAsync;
+use rstest::*;
+
+use super::client_enterprise_async;
+use super::fake_transport::FakeTransport;
+use crate::{
+    ChangeKind, ClientOptions, ConnectionState, Feature, Property, ReconnectPolicy,
+    SubscriptionFilter, Value,
+};
+
+#[rstest]
+fn test_get_ids(client_enterprise_async: AppConfigurationClientIBMCloudAsync) {
+    let mut features = client_enterprise_async.get_feature_ids().unwrap();
+    features.sort();
+    assert_eq!(features, vec!["f1", "f2", "f3", "f4", "f5", "f6"]);
+
+    let mut properties = client_enterprise_async.get_property_ids().unwrap();
+    properties.sort();
+    assert_eq!(properties, vec!["p1", "p2", "p3", "p4"]);
+}
+
+#[rstest]
+fn test_get_feature_and_property(client_enterprise_async: AppConfigurationClientIBMCloudAsync) {
+    let feature = client_enterprise_async.get_feature("f1").unwrap();
+    assert_eq!(feature.get_name().unwrap(), "F1");
+    let value = feature.get_value(&TrivialEntity).unwrap();
+    assert!(matches!(value, Value::Int64(ref v) if v == &5));
+
+    let property = client_enterprise_async.get_property("p1").unwrap();
+    let value = property.get_value(&TrivialEntity).unwrap();
+    assert!(matches!(value, Value::Int64(ref v) if v == &5));
+}
+
+#[rstest]
+fn test_proxy_uses_latest_snapshot(
+    client_enterprise_async: AppConfigurationClientIBMCloudAsync,
+    configuration_feature1_enabled: Configuration,
+) {
+    let feature = client_enterprise_async.get_feature_proxy("f1").unwrap();
+    let value = feature.get_value(&TrivialEntity).unwrap();
+    assert!(matches!(value, Value::Int64(ref v) if v == &5));
+
+    // We simulate an update of the configuration:
+    let configuration_snapshot =
+        ConfigurationSnapshot::new("environment_id", configuration_feature1_enabled).unwrap();
+    *client_enterprise_async
+        .state
+        .latest_config_snapshot
+        .lock()
+        .unwrap() = configuration_snapshot;
+
+    let value = feature.get_value(&TrivialEntity).unwrap();
+    assert!(matches!(value, Value::Int64(ref v) if v == &(-42)));
+}
+
+fn options() -> ClientOptions {
+    ClientOptions {
+        reconnect_policy: ReconnectPolicy {
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(10),
+            multiplier: 1.0,
+            jitter: 0.0,
+        },
+        logging: false,
+        ..Default::default()
+    }
+}
+
+async fn client_with(
+    transport: &FakeTransport,
+) -> crate::Result<AppConfigurationClientIBMCloudAsync> {
+    AppConfigurationClientIBMCloudAsync::new_with_transport(
+        "apikey",
+        "region",
+        "guid",
+        "environment_id",
+        "collection_id",
+        options(),
+        transport.clone(),
+    )
+    .await
+}
+
+/// Waits (up to 5 seconds) until `condition` is true, letting the monitoring task run.
+async fn wait_for(mut condition: impl FnMut() -> bool) -> bool {
+    for _ in 0..500 {
+        if condition() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    false
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_live_update_through_transport(
+    configuration_feature1_enabled: Configuration,
+    example_configuration_enterprise: Configuration,
+) {
+    let transport = FakeTransport::new(&configuration_feature1_enabled);
+    let client = client_with(&transport).await.unwrap();
+    assert_eq!(
+        client.get_connection_state().unwrap(),
+        ConnectionState::Live
+    );
+    assert!(client.get_last_successful_fetch().unwrap().is_some());
+
+    let feature = client.get_feature_proxy("f1").unwrap();
+    assert_eq!(
+        feature.get_value(&TrivialEntity).unwrap(),
+        Value::Int64(-42)
+    );
+
+    let changes = client
+        .subscribe(SubscriptionFilter::default().feature("f1"))
+        .unwrap();
+
+    // The configuration changes in the server
+    let mut configuration = example_configuration_enterprise;
+    configuration.environments[0].environment_id = "environment_id".to_string();
+    transport.update_configuration(&configuration);
+
+    let mut change = None;
+    assert!(
+        wait_for(|| {
+            change = changes.try_recv().ok();
+            change.is_some()
+        })
+        .await
+    );
+    assert!(matches!(
+        change.unwrap().features["f1"],
+        ChangeKind::Modified { .. }
+    ));
+    assert_eq!(feature.get_value(&TrivialEntity).unwrap(), Value::Int64(5));
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_reconnect_through_transport(configuration_feature1_enabled: Configuration) {
+    let transport = FakeTransport::new(&configuration_feature1_enabled);
+    let client = client_with(&transport).await.unwrap();
+
+    transport.set_offline(true);
+    assert!(
+        wait_for(|| matches!(
+            client.get_connection_state().unwrap(),
+            ConnectionState::Reconnecting { attempt, .. } if attempt > 0
+        ))
+        .await
+    );
+
+    transport.set_offline(false);
+    assert!(wait_for(|| client.get_connection_state().unwrap() == ConnectionState::Live).await);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_server_not_reachable(configuration_feature1_enabled: Configuration) {
+    let transport = FakeTransport::new(&configuration_feature1_enabled);
+    transport.set_offline(true);
+
+    let error = client_with(&transport).await.unwrap_err();
+    assert_eq!(error.to_string(), "Server not reachable");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_builder_with_transport(configuration_feature1_enabled: Configuration) {
+    let transport = FakeTransport::new(&configuration_feature1_enabled);
+    let client = crate::ClientBuilder::new()
+        .apikey("apikey")
+        .region("region")
+        .guid("guid")
+        .environment_id("environment_id")
+        .collection_id("collection_id")
+        .logging(false)
+        .build_async_with_transport(transport)
+        .await
+        .unwrap();
+    let value = client
+        .get_feature("f1")
+        .unwrap()
+        .get_value(&TrivialEntity)
+        .unwrap();
+    assert!(matches!(value, Value::Int64(ref v) if v == &(-42)));
+}
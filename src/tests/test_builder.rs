@@ -0,0 +1,88 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::client::AppConfigurationClient;
+use crate::{
+    AppConfigurationClientIBMCloud, BuilderError, ClientBuilder, ClientOptions, ConnectionState,
+    Error,
+};
+
+#[test]
+fn test_build_without_live_updates() {
+    let mut bootstrap_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    bootstrap_file.push("data/data-dump-enterprise-plan-sdk-testing.json");
+
+    // The server cannot be reached with these credentials
+    let client = ClientBuilder::new()
+        .apikey("invalid-apikey")
+        .region("invalid-region")
+        .guid("invalid-guid")
+        .environment_id("dev")
+        .collection_id("collection_id")
+        .bootstrap_file(bootstrap_file)
+        .live_updates(false)
+        .logging(false)
+        .build()
+        .unwrap();
+
+    assert_eq!(client.get_feature_ids().unwrap().len(), 6);
+    assert!(matches!(
+        client.get_connection_state().unwrap(),
+        ConnectionState::Stopped {
+            last_error: Some(_)
+        }
+    ));
+}
+
+#[test]
+fn test_build_with_invalid_settings() {
+    let result = ClientBuilder::new()
+        .apikey("apikey")
+        .region("us-south")
+        .guid("guid")
+        .collection_id("collection_id")
+        .build();
+    assert!(matches!(
+        result,
+        Err(Error::BuilderError(BuilderError::MissingSetting(
+            "environment_id"
+        )))
+    ));
+}
+
+#[test]
+fn test_new_with_invalid_options() {
+    // The options are validated before connecting to the server
+    let options = ClientOptions {
+        metering_interval: Duration::ZERO,
+        ..Default::default()
+    };
+    let result = AppConfigurationClientIBMCloud::new_with_options(
+        "apikey",
+        "region",
+        "guid",
+        "environment_id",
+        "collection_id",
+        options,
+    );
+    assert!(matches!(
+        result,
+        Err(Error::BuilderError(BuilderError::ZeroDuration(
+            "metering_interval"
+        )))
+    ));
+}
@@ -0,0 +1,110 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use rstest::*;
+
+use super::client_enterprise;
+use crate::client::feature_proxy::FeatureProxy;
+use crate::client::feature_snapshot::FeatureSnapshot;
+use crate::client::property_proxy::PropertyProxy;
+use crate::client::property_snapshot::PropertySnapshot;
+use crate::client::AppConfigurationClient;
+use crate::errors::{Error, Result};
+use crate::tests::GenericEntity;
+use crate::{AppConfigurationClientIBMCloud, SubscriptionFilter, Value};
+
+/// Client implementing only the required methods of [`AppConfigurationClient`].
+struct MinimalClient(AppConfigurationClientIBMCloud);
+
+impl AppConfigurationClient for MinimalClient {
+    fn get_feature_ids(&self) -> Result<Vec<String>> {
+        self.0.get_feature_ids()
+    }
+
+    fn get_feature(&self, feature_id: &str) -> Result<FeatureSnapshot> {
+        self.0.get_feature(feature_id)
+    }
+
+    fn get_feature_proxy<'a>(&'a self, feature_id: &str) -> Result<FeatureProxy<'a>> {
+        Ok(FeatureProxy::new(self, feature_id.to_string()))
+    }
+
+    fn get_property_ids(&self) -> Result<Vec<String>> {
+        self.0.get_property_ids()
+    }
+
+    fn get_property(&self, property_id: &str) -> Result<PropertySnapshot> {
+        self.0.get_property(property_id)
+    }
+
+    fn get_property_proxy(&self, property_id: &str) -> Result<PropertyProxy<'_>> {
+        Ok(PropertyProxy::new(self, property_id.to_string()))
+    }
+}
+
+#[fixture]
+fn minimal_client(client_enterprise: AppConfigurationClientIBMCloud) -> MinimalClient {
+    MinimalClient(client_enterprise)
+}
+
+#[rstest]
+fn test_default_subscriptions(minimal_client: MinimalClient) {
+    assert!(matches!(
+        minimal_client.subscribe(SubscriptionFilter::default()),
+        Err(Error::Other(_))
+    ));
+    assert!(matches!(
+        minimal_client.subscribe_with_callback(SubscriptionFilter::default(), Box::new(|_| {})),
+        Err(Error::Other(_))
+    ));
+}
+
+#[rstest]
+fn test_default_evaluate_all(minimal_client: MinimalClient) {
+    // Only segment 'l2dfo8do', used by 'f1' and 'p1', fails to evaluate
+    let entity = GenericEntity {
+        id: "a2".into(),
+        attributes: HashMap::from([
+            ("email".into(), Value::from("adi@ibm.com".to_string())),
+            ("code".into(), Value::from(true)),
+        ]),
+    };
+    let evaluated = minimal_client.evaluate_all(&entity).unwrap();
+    let expected = minimal_client.0.evaluate_all(&entity).unwrap();
+
+    assert_eq!(evaluated.features, expected.features);
+    assert_eq!(evaluated.properties, expected.properties);
+    assert_eq!(
+        evaluated.feature_errors.keys().collect::<Vec<_>>(),
+        vec!["f1"]
+    );
+    assert_eq!(
+        evaluated.property_errors.keys().collect::<Vec<_>>(),
+        vec!["p1"]
+    );
+}
+
+#[rstest]
+fn test_default_evaluate_bootstrap(minimal_client: MinimalClient) {
+    let entity = GenericEntity {
+        id: "a2".into(),
+        attributes: HashMap::new(),
+    };
+    assert!(matches!(
+        minimal_client.evaluate_bootstrap(&entity),
+        Err(Error::Other(_))
+    ));
+}
@@ -0,0 +1,55 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use crate::client::AppConfigurationClientIBMCloud;
+use crate::ConnectionState;
+use rstest::*;
+
+use super::client_enterprise;
+
+#[rstest]
+fn test_connection_status(client_enterprise: AppConfigurationClientIBMCloud) {
+    assert_eq!(
+        client_enterprise.get_connection_state().unwrap(),
+        ConnectionState::Stopped { last_error: None }
+    );
+    assert!(client_enterprise
+        .get_last_successful_fetch()
+        .unwrap()
+        .is_none());
+
+    // Simulate the monitoring thread receiving a configuration
+    {
+        let mut status = client_enterprise.state.status.lock().unwrap();
+        status.record_fetch();
+        status.set_live();
+    }
+    assert_eq!(
+        client_enterprise.get_connection_state().unwrap(),
+        ConnectionState::Live
+    );
+    assert!(client_enterprise
+        .get_last_successful_fetch()
+        .unwrap()
+        .is_some());
+}
+
+#[rstest]
+fn test_snapshot_age(client_enterprise: AppConfigurationClientIBMCloud) {
+    let age = client_enterprise.get_snapshot_age().unwrap();
+    std::thread::sleep(Duration::from_millis(10));
+    assert!(client_enterprise.get_snapshot_age().unwrap() >= age + Duration::from_millis(10));
+}
@@ -0,0 +1,192 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::client::AppConfigurationClient;
+use crate::tests::GenericEntity;
+use crate::{ClientBuilder, ConnectionState, Endpoints, Feature, Property, Value};
+
+/// Requests received by the local server: method, path and authorization header, and the body.
+type ReceivedRequests = Arc<Mutex<Vec<(String, String)>>>;
+
+/// Starts a server answering `requests` HTTP requests like IAM and App Configuration
+/// would do. Returns its URL and the list of requests received.
+fn start_local_server(requests: usize) -> (String, ReceivedRequests) {
+    let mut data_dump = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    data_dump.push("data/data-dump-enterprise-plan-sdk-testing.json");
+    let configuration = std::fs::read_to_string(data_dump).unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let log = received.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming().take(requests) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            let mut authorization = String::new();
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                let header = header.trim_end();
+                if header.is_empty() {
+                    break;
+                }
+                let (name, value) = header.split_once(": ").unwrap();
+                match name.to_lowercase().as_str() {
+                    "content-length" => content_length = value.parse().unwrap(),
+                    "authorization" => authorization = value.to_string(),
+                    _ => {}
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let mut parts = request_line.split_whitespace();
+            let method = parts.next().unwrap().to_string();
+            let path = parts.next().unwrap().to_string();
+            let response = if path == "/identity/token" {
+                r#"{"access_token": "local-token", "expires_in": 3600}"#
+            } else if path.ends_with("/usage") {
+                "{}"
+            } else {
+                configuration.as_str()
+            };
+            log.lock().unwrap().push((
+                format!("{method} {path} {authorization}"),
+                String::from_utf8(body).unwrap(),
+            ));
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            )
+            .unwrap();
+        }
+    });
+    (url, received)
+}
+
+#[test]
+fn test_client_using_local_server() {
+    let (url, requests) = start_local_server(2);
+
+    let client = ClientBuilder::new()
+        .apikey("apikey")
+        .guid("guid")
+        .environment_id("dev")
+        .collection_id("collection_id")
+        .endpoints(Endpoints::local_server(&url))
+        .live_updates(false)
+        .build()
+        .unwrap();
+
+    assert_eq!(client.get_feature_ids().unwrap().len(), 6);
+    assert_eq!(
+        client.get_connection_state().unwrap(),
+        ConnectionState::Stopped { last_error: None }
+    );
+    assert!(client.get_last_successful_fetch().unwrap().is_some());
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(
+        requests.iter().map(|(request, _)| request.as_str()).collect::<Vec<_>>(),
+        vec![
+            "POST /identity/token ".to_string(),
+            "GET /apprapp/feature/v1/instances/guid/config?action=sdkConfig&collection_id=collection_id&environment_id=dev Bearer local-token".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn test_usage_metering_using_local_server() {
+    let (url, requests) = start_local_server(3);
+
+    let client = ClientBuilder::new()
+        .apikey("apikey")
+        .guid("guid")
+        .environment_id("dev")
+        .collection_id("collection_id")
+        .endpoints(Endpoints::local_server(&url))
+        .live_updates(false)
+        .usage_metering(true)
+        .build()
+        .unwrap();
+
+    let entity = GenericEntity {
+        id: "a1".into(),
+        attributes: HashMap::from([("email".into(), Value::from("user@example.com".to_string()))]),
+    };
+    for _ in 0..3 {
+        client
+            .get_feature("f1")
+            .unwrap()
+            .get_value(&entity)
+            .unwrap();
+    }
+    client
+        .get_property_proxy("p1")
+        .unwrap()
+        .get_value(&entity)
+        .unwrap();
+
+    // The usage recorded so far is sent when the client is dropped
+    drop(client);
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while requests.lock().unwrap().len() < 3 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let requests = requests.lock().unwrap();
+    let (request, body) = &requests[2];
+    assert_eq!(
+        request,
+        "POST /apprapp/events/v1/instances/guid/usage Bearer local-token"
+    );
+    let body: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(body["collection_id"], "collection_id");
+    assert_eq!(body["environment_id"], "dev");
+    let mut usages = body["usages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|usage| {
+            let mut usage = usage.clone();
+            assert!(usage["evaluation_time"].is_string());
+            usage.as_object_mut().unwrap().remove("evaluation_time");
+            usage
+        })
+        .collect::<Vec<_>>();
+    usages.sort_by_key(|usage| usage.to_string());
+    assert_eq!(
+        usages,
+        vec![
+            serde_json::json!({"count": 1, "entity_id": "a1", "property_id": "p1", "segment_id": "$$null$$"}),
+            serde_json::json!({"count": 3, "entity_id": "a1", "feature_id": "f1", "segment_id": "$$null$$"}),
+        ]
+    );
+}
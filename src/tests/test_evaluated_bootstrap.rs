@@ -0,0 +1,267 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use rstest::*;
+
+use super::client_enterprise;
+use crate::client::cache::ConfigurationSnapshot;
+use crate::client::{AppConfigurationClient, AppConfigurationClientIBMCloud};
+use crate::errors::{ConfigurationAccessError, Error};
+use crate::models::tests::example_configuration_enterprise;
+use crate::models::{ConfigValue, Configuration, ValueFormat};
+use crate::tests::GenericEntity;
+use crate::{
+    AppConfigurationEvaluated, EvaluatedBootstrap, EvaluatedFeature, EvaluatedProperty, Feature,
+    Property, Value,
+};
+
+#[fixture]
+fn entity() -> GenericEntity {
+    GenericEntity {
+        id: "a2".into(),
+        attributes: HashMap::from([
+            ("email".into(), Value::from("adi@ibm.com".to_string())),
+            ("number".into(), Value::from(12.0)),
+        ]),
+    }
+}
+
+#[rstest]
+fn test_evaluated_bootstrap_roundtrip(
+    client_enterprise: AppConfigurationClientIBMCloud,
+    entity: GenericEntity,
+) {
+    let bootstrap = client_enterprise.evaluate_bootstrap(&entity).unwrap();
+    assert_eq!(bootstrap.entity_id, "a2");
+    assert_eq!(
+        bootstrap.version,
+        client_enterprise
+            .state
+            .latest_config_snapshot
+            .lock()
+            .unwrap()
+            .version
+    );
+
+    // No targeting information is exposed
+    let json = serde_json::to_string(&bootstrap).unwrap();
+    assert!(!json.contains("segment"));
+    assert!(!json.contains("adi@ibm.com"));
+
+    let client = AppConfigurationEvaluated::from_json_str(&json).unwrap();
+    assert_eq!(client.get_version(), bootstrap.version);
+
+    let mut feature_ids = client.get_feature_ids();
+    feature_ids.sort();
+    let mut expected_ids = client_enterprise.get_feature_ids().unwrap();
+    expected_ids.sort();
+    assert_eq!(feature_ids, expected_ids);
+    for feature_id in feature_ids {
+        let expected = client_enterprise.get_feature(&feature_id).unwrap();
+        let feature = client.get_feature(&feature_id).unwrap();
+        assert_eq!(feature.get_name().unwrap(), expected.get_name().unwrap());
+        assert_eq!(
+            feature.is_enabled().unwrap(),
+            expected.is_enabled().unwrap()
+        );
+        assert_eq!(
+            feature.get_value(&entity).unwrap(),
+            expected.get_value(&entity).unwrap()
+        );
+    }
+
+    assert_eq!(
+        client.get_property_ids().len(),
+        client_enterprise.get_property_ids().unwrap().len()
+    );
+    let property = client.get_property("p1").unwrap();
+    assert_eq!(property.get_name().unwrap(), "p1");
+    let value: i64 = property.get_value_into(&entity).unwrap();
+    assert_eq!(value, 5);
+}
+
+#[rstest]
+fn test_evaluated_bootstrap_errors(
+    client_enterprise: AppConfigurationClientIBMCloud,
+    entity: GenericEntity,
+) {
+    let bootstrap = client_enterprise.evaluate_bootstrap(&entity).unwrap();
+    let client = AppConfigurationEvaluated::new(bootstrap);
+
+    let other_entity = GenericEntity {
+        id: "a1".into(),
+        attributes: HashMap::new(),
+    };
+    let feature = client.get_feature("f1").unwrap();
+    let Error::EntityMismatch {
+        expected_id,
+        entity_id,
+    } = feature.get_value(&other_entity).unwrap_err()
+    else {
+        panic!("Error type mismatch!");
+    };
+    assert_eq!(expected_id, "a2");
+    assert_eq!(entity_id, "a1");
+
+    assert!(matches!(
+        client.get_feature("does_not_exist").unwrap_err(),
+        Error::ConfigurationAccessError(ConfigurationAccessError::FeatureNotFound { .. })
+    ));
+    assert!(matches!(
+        client.get_property("does_not_exist").unwrap_err(),
+        Error::ConfigurationAccessError(ConfigurationAccessError::PropertyNotFound { .. })
+    ));
+}
+
+#[rstest]
+fn test_evaluated_bootstrap_leaves_out_failures(client_enterprise: AppConfigurationClientIBMCloud) {
+    let entity = GenericEntity {
+        id: "a2".into(),
+        attributes: HashMap::from([("code".into(), Value::from(true))]),
+    };
+    let bootstrap = client_enterprise.evaluate_bootstrap(&entity).unwrap();
+    assert!(!bootstrap.features.contains_key("f1"));
+    assert!(bootstrap.features.contains_key("f2"));
+    assert!(!bootstrap.properties.contains_key("p1"));
+    assert!(bootstrap.properties.contains_key("p2"));
+}
+
+#[rstest]
+fn test_evaluated_bootstrap_roundtrip_json_values(
+    client_enterprise: AppConfigurationClientIBMCloud,
+    mut example_configuration_enterprise: Configuration,
+    entity: GenericEntity,
+) {
+    // Feature 'f2' holds a JSON string and property 'p2' a JSON number
+    let environment = &mut example_configuration_enterprise.environments[0];
+    let feature = environment
+        .features
+        .iter_mut()
+        .find(|f| f.feature_id == "f2")
+        .unwrap();
+    feature.format = Some(ValueFormat::Json);
+    feature.enabled_value = ConfigValue(serde_json::json!(r#""large""#));
+    let property = environment
+        .properties
+        .iter_mut()
+        .find(|p| p.property_id == "p2")
+        .unwrap();
+    property.format = Some(ValueFormat::Json);
+    property.value = ConfigValue(serde_json::json!("5"));
+    *client_enterprise
+        .state
+        .latest_config_snapshot
+        .lock()
+        .unwrap() = ConfigurationSnapshot::new("dev", example_configuration_enterprise).unwrap();
+
+    let bootstrap = client_enterprise.evaluate_bootstrap(&entity).unwrap();
+    let json = serde_json::to_string(&bootstrap).unwrap();
+    let client = AppConfigurationEvaluated::from_json_str(&json).unwrap();
+
+    let feature = client.get_feature("f2").unwrap();
+    let expected = client_enterprise.get_feature("f2").unwrap();
+    assert_eq!(
+        feature.get_value(&entity).unwrap(),
+        Value::Structured(serde_json::json!("large"))
+    );
+    assert_eq!(
+        feature.get_value(&entity).unwrap(),
+        expected.get_value(&entity).unwrap()
+    );
+    let value: serde_json::Value = feature.get_value_into(&entity).unwrap();
+    assert_eq!(value, serde_json::json!("large"));
+
+    let property = client.get_property("p2").unwrap();
+    assert_eq!(
+        property.get_value(&entity).unwrap(),
+        Value::Structured(serde_json::json!(5))
+    );
+    let value: serde_json::Value = property.get_value_into(&entity).unwrap();
+    assert_eq!(value, serde_json::json!(5));
+}
+
+#[test]
+fn test_evaluated_bootstrap_roundtrip_value_types() {
+    let values = [
+        Value::UInt64(5),
+        Value::Int64(5),
+        Value::Float64(5.0),
+        Value::String("5".into()),
+        Value::Boolean(true),
+        Value::Structured(serde_json::json!("5")),
+        Value::Structured(serde_json::json!(5)),
+    ];
+    let bootstrap = EvaluatedBootstrap {
+        version: "v1".into(),
+        entity_id: "a2".into(),
+        features: values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let feature = EvaluatedFeature {
+                    name: format!("F{i}"),
+                    enabled: true,
+                    value: value.clone(),
+                };
+                (format!("f{i}"), feature)
+            })
+            .collect(),
+        properties: values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let property = EvaluatedProperty {
+                    name: format!("P{i}"),
+                    value: value.clone(),
+                };
+                (format!("p{i}"), property)
+            })
+            .collect(),
+    };
+
+    let json = serde_json::to_string(&bootstrap).unwrap();
+    let read: EvaluatedBootstrap = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, bootstrap);
+
+    // Values are still plain JSON values
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["features"]["f0"]["value"], serde_json::json!(5));
+    assert_eq!(json["features"]["f0"]["type"], "uint64");
+}
+
+#[test]
+fn test_evaluated_bootstrap_type_mismatch() {
+    let json = r#"{"version": "v1", "entity_id": "a2", "properties": {},
+        "features": {"f1": {"name": "F1", "enabled": true, "value": "5", "type": "uint64"}}}"#;
+    assert!(AppConfigurationEvaluated::from_json_str(json).is_err());
+
+    // Values without type information are read as untyped JSON values
+    let json = r#"{"version": "v1", "entity_id": "a2", "properties": {},
+        "features": {"f1": {"name": "F1", "enabled": true, "value": 5}}}"#;
+    let client = AppConfigurationEvaluated::from_json_str(json).unwrap();
+    let entity = GenericEntity {
+        id: "a2".into(),
+        attributes: HashMap::new(),
+    };
+    assert_eq!(
+        client
+            .get_feature("f1")
+            .unwrap()
+            .get_value(&entity)
+            .unwrap(),
+        Value::Int64(5)
+    );
+}
@@ -35,7 +35,11 @@ fn test_get_feature_persistence(
     // We simulate an update of the configuration:
     let configuration_snapshot =
         ConfigurationSnapshot::new("environment_id", configuration_feature1_enabled).unwrap();
-    *client_enterprise.latest_config_snapshot.lock().unwrap() = configuration_snapshot;
+    *client_enterprise
+        .state
+        .latest_config_snapshot
+        .lock()
+        .unwrap() = configuration_snapshot;
     // The feature value should not have changed (as we did not retrieve it again)
     let feature_value2 = feature.get_value(&entity).unwrap();
     assert_eq!(feature_value2, feature_value1);
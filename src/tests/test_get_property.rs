@@ -35,7 +35,11 @@ fn test_get_property_persistence(
     // We simulate an update of the configuration:
     let configuration_snapshot =
         ConfigurationSnapshot::new("environment_id", configuration_property1_enabled).unwrap();
-    *client_enterprise.latest_config_snapshot.lock().unwrap() = configuration_snapshot;
+    *client_enterprise
+        .state
+        .latest_config_snapshot
+        .lock()
+        .unwrap() = configuration_snapshot;
     // The property value should not have changed (as we did not retrieve it again)
     let property_value2 = property.get_value(&entity).unwrap();
     assert_eq!(property_value2, property_value1);
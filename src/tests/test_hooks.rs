@@ -0,0 +1,181 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use rstest::*;
+
+use super::client_enterprise;
+use crate::client::AppConfigurationClient;
+use crate::hooks::Hooks;
+use crate::tests::{GenericEntity, TrivialEntity};
+use crate::{
+    AppConfigurationClientIBMCloud, Error, EvaluationDetails, EvaluationHook, Feature, HookContext,
+    Property, Value,
+};
+
+/// Hook recording every call it receives.
+#[derive(Debug, Default)]
+struct RecordingHook {
+    calls: Mutex<Vec<String>>,
+}
+
+impl RecordingHook {
+    fn take_calls(&self) -> Vec<String> {
+        std::mem::take(&mut *self.calls.lock().unwrap())
+    }
+}
+
+impl EvaluationHook for RecordingHook {
+    fn before(&self, context: &HookContext<'_>) {
+        self.calls.lock().unwrap().push(format!(
+            "before {:?} {} {}",
+            context.kind,
+            context.id,
+            context.entity.get_id()
+        ));
+    }
+
+    fn after(&self, context: &HookContext<'_>, details: &EvaluationDetails) {
+        self.calls.lock().unwrap().push(format!(
+            "after {:?} {} {:?} {:?}",
+            context.kind, context.id, details.value, details.reason
+        ));
+    }
+
+    fn error(&self, context: &HookContext<'_>, error: &Error) {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("error {:?} {} {}", context.kind, context.id, error));
+    }
+}
+
+/// Hook recording its name in a log shared with other hooks.
+#[derive(Debug)]
+struct NamedHook {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl EvaluationHook for NamedHook {
+    fn before(&self, _context: &HookContext<'_>) {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("before {}", self.name));
+    }
+
+    fn after(&self, _context: &HookContext<'_>, _details: &EvaluationDetails) {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("after {}", self.name));
+    }
+}
+
+#[rstest]
+fn test_hooks_on_snapshots(mut client_enterprise: AppConfigurationClientIBMCloud) {
+    let hook = Arc::new(RecordingHook::default());
+    client_enterprise.state.hooks = Hooks::new(vec![hook.clone()]);
+
+    let feature = client_enterprise.get_feature("f1").unwrap();
+    assert_eq!(feature.get_value(&TrivialEntity).unwrap(), Value::Int64(5));
+    let property = client_enterprise.get_property("p1").unwrap();
+    assert_eq!(property.get_value(&TrivialEntity).unwrap(), Value::Int64(5));
+
+    assert_eq!(
+        hook.take_calls(),
+        vec![
+            "before Feature f1 TrivialId",
+            "after Feature f1 Int64(5) Default",
+            "before Property p1 TrivialId",
+            "after Property p1 Int64(5) Default",
+        ]
+    );
+}
+
+#[rstest]
+fn test_hooks_on_proxies(mut client_enterprise: AppConfigurationClientIBMCloud) {
+    let hook = Arc::new(RecordingHook::default());
+    client_enterprise.state.hooks = Hooks::new(vec![hook.clone()]);
+
+    let feature = client_enterprise.get_feature_proxy("f1").unwrap();
+    let value: u8 = feature.get_value_as(&TrivialEntity).unwrap();
+    assert_eq!(value, 5);
+    let property = client_enterprise.get_property_proxy("p1").unwrap();
+    let value: i64 = property.get_value_into(&TrivialEntity).unwrap();
+    assert_eq!(value, 5);
+
+    assert_eq!(
+        hook.take_calls(),
+        vec![
+            "before Feature f1 TrivialId",
+            "after Feature f1 Int64(5) Default",
+            "before Property p1 TrivialId",
+            "after Property p1 Int64(5) Default",
+        ]
+    );
+}
+
+#[rstest]
+fn test_hooks_on_error(mut client_enterprise: AppConfigurationClientIBMCloud) {
+    let hook = Arc::new(RecordingHook::default());
+    client_enterprise.state.hooks = Hooks::new(vec![hook.clone()]);
+
+    // The segment matching on `code` expects a string
+    let entity = GenericEntity {
+        id: "a1".into(),
+        attributes: HashMap::from([("code".into(), Value::Boolean(true))]),
+    };
+    let feature = client_enterprise.get_feature("f1").unwrap();
+    let error = feature.get_value(&entity).unwrap_err();
+
+    assert_eq!(
+        hook.take_calls(),
+        vec![
+            "before Feature f1 a1".to_string(),
+            format!("error Feature f1 {error}"),
+        ]
+    );
+}
+
+#[rstest]
+fn test_hooks_run_in_order(mut client_enterprise: AppConfigurationClientIBMCloud) {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    client_enterprise.state.hooks = Hooks::new(vec![
+        Arc::new(NamedHook {
+            name: "first",
+            log: log.clone(),
+        }),
+        Arc::new(NamedHook {
+            name: "second",
+            log: log.clone(),
+        }),
+    ]);
+
+    let feature = client_enterprise.get_feature("f1").unwrap();
+    feature.get_value(&TrivialEntity).unwrap();
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "before first",
+            "before second",
+            "after first",
+            "after second"
+        ]
+    );
+}
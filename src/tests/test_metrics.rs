@@ -0,0 +1,211 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use metrics::{
+    Counter, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+};
+use rstest::*;
+
+use super::fake_transport::FakeTransport;
+use crate::client::context::ConnectionContext;
+use crate::client::monitor::ClientState;
+use crate::client::AppConfigurationClient;
+use crate::models::tests::example_configuration_enterprise;
+use crate::models::Configuration;
+use crate::tests::GenericEntity;
+use crate::{AppConfigurationClientIBMCloud, ClientOptions, Feature, Property, Value};
+
+/// [`Recorder`] keeping the value of every counter and gauge, and the number of
+/// values recorded by every histogram, by name and labels.
+#[derive(Default)]
+struct TestRecorder {
+    metrics: Mutex<BTreeMap<String, Arc<AtomicU64>>>,
+}
+
+struct HistogramCount(Arc<AtomicU64>);
+
+impl HistogramFn for HistogramCount {
+    fn record(&self, _value: f64) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+impl TestRecorder {
+    fn register(&self, key: &Key) -> Arc<AtomicU64> {
+        let labels = key
+            .labels()
+            .map(|label| format!("{}={}", label.key(), label.value()))
+            .collect::<Vec<_>>();
+        let name = format!("{}{{{}}}", key.name(), labels.join(","));
+        self.metrics
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_default()
+            .clone()
+    }
+
+    fn value(&self, name: &str) -> Option<u64> {
+        let metrics = self.metrics.lock().unwrap();
+        metrics.get(name).map(|value| value.load(Ordering::SeqCst))
+    }
+}
+
+impl Recorder for TestRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _metadata: &Metadata<'_>) -> Counter {
+        Counter::from_arc(self.register(key))
+    }
+
+    fn register_gauge(&self, key: &Key, _metadata: &Metadata<'_>) -> Gauge {
+        Gauge::from_arc(self.register(key))
+    }
+
+    fn register_histogram(&self, key: &Key, _metadata: &Metadata<'_>) -> Histogram {
+        Histogram::from_arc(Arc::new(HistogramCount(self.register(key))))
+    }
+}
+
+fn create_client(transport: &FakeTransport) -> crate::Result<AppConfigurationClientIBMCloud> {
+    let options = ClientOptions {
+        live_updates: false,
+        logging: false,
+        ..Default::default()
+    };
+    AppConfigurationClientIBMCloud::new_with_transport(
+        "apikey",
+        "region",
+        "guid",
+        "dev",
+        "collection_id",
+        options,
+        transport.clone(),
+    )
+}
+
+#[rstest]
+fn test_configuration_fetch_metrics(example_configuration_enterprise: Configuration) {
+    let recorder = TestRecorder::default();
+    let transport = FakeTransport::new(&example_configuration_enterprise);
+
+    metrics::with_local_recorder(&recorder, || {
+        create_client(&transport).unwrap();
+        transport.set_offline(true);
+        assert!(create_client(&transport).is_err());
+    });
+
+    let labels = "{environment_id=dev,collection_id=collection_id}";
+    assert_eq!(
+        recorder.value(&format!(
+            "appconfiguration_configuration_fetch_duration_seconds{labels}"
+        )),
+        Some(2)
+    );
+    assert_eq!(
+        recorder.value(&format!(
+            "appconfiguration_configuration_fetch_failures_total{labels}"
+        )),
+        Some(1)
+    );
+}
+
+#[rstest]
+fn test_evaluation_metrics(example_configuration_enterprise: Configuration) {
+    let recorder = TestRecorder::default();
+    let transport = FakeTransport::new(&example_configuration_enterprise);
+    let client = create_client(&transport).unwrap();
+
+    let entity = GenericEntity {
+        id: "a1".into(),
+        attributes: HashMap::from([("size".into(), Value::from("10UK".to_string()))]),
+    };
+    // The segment matching on `code` expects a string
+    let invalid_entity = GenericEntity {
+        id: "a1".into(),
+        attributes: HashMap::from([("code".into(), Value::Boolean(true))]),
+    };
+    metrics::with_local_recorder(&recorder, || {
+        let feature = client.get_feature("f1").unwrap();
+        feature.get_value(&entity).unwrap();
+        feature.get_value(&entity).unwrap();
+        assert!(feature.get_value(&invalid_entity).is_err());
+        client
+            .get_property("p3")
+            .unwrap()
+            .get_value(&entity)
+            .unwrap();
+    });
+
+    assert_eq!(
+        recorder.value("appconfiguration_evaluations_total{kind=feature,id=f1,reason=default}"),
+        Some(2)
+    );
+    assert_eq!(
+        recorder.value("appconfiguration_evaluations_total{kind=property,id=p3,reason=default}"),
+        Some(1)
+    );
+    assert_eq!(
+        recorder.value(
+            "appconfiguration_evaluation_errors_total{kind=feature,id=f1,error=string_expected}"
+        ),
+        Some(1)
+    );
+    assert_eq!(
+        recorder.value("appconfiguration_evaluation_duration_seconds{kind=feature,id=f1}"),
+        Some(3)
+    );
+    assert_eq!(
+        recorder.value("appconfiguration_evaluation_duration_seconds{kind=property,id=p3}"),
+        Some(1)
+    );
+    assert!(recorder
+        .value("appconfiguration_snapshot_age_seconds{environment_id=dev}")
+        .is_some());
+}
+
+#[rstest]
+fn test_snapshot_age_metric_from_monitor(example_configuration_enterprise: Configuration) {
+    let recorder = TestRecorder::default();
+    let transport = FakeTransport::new(&example_configuration_enterprise);
+    let context = Arc::new(ConnectionContext::new(
+        "apikey",
+        "region",
+        "guid",
+        "dev",
+        "collection_id",
+        ClientOptions::default(),
+        transport,
+    ));
+    let state = ClientState::new(context.get_configuration_snapshot().unwrap());
+    let monitor = state.monitor(context);
+    let gauge = "appconfiguration_snapshot_age_seconds{environment_id=dev}";
+
+    // Nothing is evaluated, the monitoring loop keeps the gauge up to date
+    metrics::with_local_recorder(&recorder, || monitor.tick().unwrap());
+    assert!(recorder.value(gauge).is_some());
+
+    let recorder = TestRecorder::default();
+    metrics::with_local_recorder(&recorder, || {
+        let config_snapshot = monitor.context.get_configuration_snapshot();
+        assert!(monitor.updated(config_snapshot).unwrap());
+    });
+    assert!(recorder.value(gauge).is_some());
+}
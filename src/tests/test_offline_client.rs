@@ -0,0 +1,83 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::client::{AppConfigurationClient, AppConfigurationOffline};
+use crate::tests::TrivialEntity;
+use crate::{Error, Feature, Property, Value};
+use rstest::*;
+use std::path::PathBuf;
+
+#[fixture]
+fn data_dump_enterprise() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("data/data-dump-enterprise-plan-sdk-testing.json");
+    path
+}
+
+#[rstest]
+fn test_offline_client_from_path(data_dump_enterprise: PathBuf) {
+    let client = AppConfigurationOffline::from_path(data_dump_enterprise, "dev").unwrap();
+
+    let mut features = client.get_feature_ids().unwrap();
+    features.sort();
+    assert_eq!(features, vec!["f1", "f2", "f3", "f4", "f5", "f6"]);
+
+    let feature = client.get_feature("f1").unwrap();
+    assert_eq!(feature.get_name().unwrap(), "F1");
+    let value = feature.get_value(&TrivialEntity).unwrap();
+    assert!(matches!(value, Value::Int64(ref v) if v == &5));
+
+    let property = client.get_property_proxy("p1").unwrap();
+    let value = property.get_value(&TrivialEntity).unwrap();
+    assert!(matches!(value, Value::Int64(ref v) if v == &5));
+}
+
+#[rstest]
+fn test_offline_client_from_reader_and_str(data_dump_enterprise: PathBuf) {
+    let file = std::fs::File::open(&data_dump_enterprise).unwrap();
+    let from_reader = AppConfigurationOffline::from_reader(file, "dev").unwrap();
+
+    let json = std::fs::read_to_string(&data_dump_enterprise).unwrap();
+    let from_str = AppConfigurationOffline::from_json_str(&json, "dev").unwrap();
+
+    assert_eq!(
+        from_reader.config_snapshot.features,
+        from_str.config_snapshot.features
+    );
+    assert_eq!(
+        from_reader.config_snapshot.properties,
+        from_str.config_snapshot.properties
+    );
+}
+
+#[rstest]
+fn test_offline_client_errors(data_dump_enterprise: PathBuf) {
+    let result = AppConfigurationOffline::from_path("does/not/exist.json", "dev");
+    assert!(matches!(result, Err(Error::IoError(_))));
+
+    let result = AppConfigurationOffline::from_json_str("{\"environments\": 42}", "dev");
+    assert!(matches!(result, Err(Error::ConfigurationParseError(_))));
+
+    let result = AppConfigurationOffline::from_path(data_dump_enterprise, "production");
+    assert!(matches!(result, Err(Error::ConfigurationAccessError(_))));
+}
+
+#[rstest]
+fn test_offline_client_subscriptions(data_dump_enterprise: PathBuf) {
+    let client = AppConfigurationOffline::from_path(data_dump_enterprise, "dev").unwrap();
+    assert!(matches!(
+        client.subscribe(crate::SubscriptionFilter::default()),
+        Err(Error::Other(_))
+    ));
+}
@@ -0,0 +1,112 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use open_feature::provider::FeatureProvider;
+use open_feature::{EvaluationContext, EvaluationErrorCode, EvaluationReason, FlagMetadataValue};
+use rstest::*;
+
+use super::client_enterprise;
+use crate::client::AppConfigurationClientIBMCloud;
+use crate::AppConfigurationProvider;
+
+fn context_in_segment() -> EvaluationContext {
+    EvaluationContext::default()
+        .with_targeting_key("a2")
+        .with_custom_field("email", "adi@ibm.com")
+        .with_custom_field("number", 12.0)
+        .with_custom_field("numbers", 12)
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_resolve_default_values(client_enterprise: AppConfigurationClientIBMCloud) {
+    let provider = AppConfigurationProvider::new(client_enterprise);
+    let context = EvaluationContext::default().with_targeting_key("a1");
+
+    let details = provider.resolve_int_value("f1", &context).await.unwrap();
+    assert_eq!(details.value, 5);
+    assert_eq!(details.reason, Some(EvaluationReason::Default));
+
+    let details = provider.resolve_float_value("p1", &context).await.unwrap();
+    assert_eq!(details.value, 5.0);
+
+    let details = provider.resolve_bool_value("f3", &context).await.unwrap();
+    assert!(details.value);
+
+    let details = provider.resolve_string_value("p4", &context).await.unwrap();
+    assert_eq!(details.value, "my text");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_resolve_targeting_match(client_enterprise: AppConfigurationClientIBMCloud) {
+    let provider = AppConfigurationProvider::new(client_enterprise);
+
+    let details = provider
+        .resolve_int_value("f1", &context_in_segment())
+        .await
+        .unwrap();
+    assert_eq!(details.value, 25);
+    assert_eq!(details.reason, Some(EvaluationReason::TargetingMatch));
+    let metadata = details.flag_metadata.unwrap();
+    assert_eq!(
+        metadata.values.get("segment_id"),
+        Some(&FlagMetadataValue::String("l2dfr61d".into()))
+    );
+    assert_eq!(
+        metadata.values.get("rule_order"),
+        Some(&FlagMetadataValue::Int(3))
+    );
+
+    let details = provider
+        .resolve_int_value("p1", &context_in_segment())
+        .await
+        .unwrap();
+    assert_eq!(details.value, 25);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_resolve_errors(client_enterprise: AppConfigurationClientIBMCloud) {
+    let provider = AppConfigurationProvider::new(client_enterprise);
+    let context = EvaluationContext::default().with_targeting_key("a1");
+
+    let error = provider
+        .resolve_bool_value("unknown", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::FlagNotFound);
+
+    let error = provider
+        .resolve_string_value("f1", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TypeMismatch);
+
+    let error = provider
+        .resolve_int_value("f1", &EvaluationContext::default())
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::TargetingKeyMissing);
+
+    let context = context.with_custom_field(
+        "address",
+        open_feature::EvaluationContextFieldValue::new_struct(String::from("street")),
+    );
+    let error = provider
+        .resolve_int_value("f1", &context)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code, EvaluationErrorCode::InvalidContext);
+}
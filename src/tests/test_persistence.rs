@@ -0,0 +1,74 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+use crate::client::{AppConfigurationClient, AppConfigurationClientIBMCloud};
+use crate::tests::event_capture::capture_events;
+use crate::{ClientOptions, ConnectionState};
+
+#[test]
+fn test_client_starts_from_bootstrap_file() {
+    let mut bootstrap_file = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    bootstrap_file.push("data/data-dump-enterprise-plan-sdk-testing.json");
+    let options = ClientOptions {
+        bootstrap_file: Some(bootstrap_file),
+        ..Default::default()
+    };
+
+    // The server cannot be reached with these credentials
+    let (client, events) = capture_events(|| {
+        AppConfigurationClientIBMCloud::new_with_options(
+            "invalid-apikey",
+            "invalid-region",
+            "invalid-guid",
+            "dev",
+            "collection_id",
+            options,
+        )
+    });
+    let client = client.unwrap();
+    let warning = events
+        .iter()
+        .find(|event| event.starts_with("WARN "))
+        .unwrap();
+    assert!(warning
+        .starts_with("WARN Cannot fetch configuration from the server. Using local copy error="));
+    assert!(warning.ends_with("[guid=invalid-guid environment_id=dev collection_id=collection_id]"));
+
+    assert_eq!(client.get_feature_ids().unwrap().len(), 6);
+    assert!(matches!(
+        client.get_connection_state().unwrap(),
+        ConnectionState::Reconnecting { attempt: 0, .. }
+    ));
+    assert!(client.get_last_successful_fetch().unwrap().is_none());
+}
+
+#[test]
+fn test_client_fails_without_local_copies() {
+    let options = ClientOptions {
+        cache_file: Some(std::env::temp_dir().join("appconfiguration-does-not-exist.json")),
+        ..Default::default()
+    };
+
+    let client = AppConfigurationClientIBMCloud::new_with_options(
+        "invalid-apikey",
+        "invalid-region",
+        "invalid-guid",
+        "dev",
+        "collection_id",
+        options,
+    );
+    assert!(client.is_err());
+}
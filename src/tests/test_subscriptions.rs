@@ -0,0 +1,57 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::client::cache::ConfigurationSnapshot;
+use crate::client::subscriptions::update_configuration_snapshot;
+use crate::client::{AppConfigurationClient, AppConfigurationClientIBMCloud};
+use crate::models::tests::configuration_feature1_enabled;
+use crate::models::Configuration;
+use crate::{ChangeKind, SubscriptionFilter};
+use rstest::*;
+
+use super::client_enterprise;
+
+#[rstest]
+fn test_subscribe_to_feature(
+    client_enterprise: AppConfigurationClientIBMCloud,
+    configuration_feature1_enabled: Configuration,
+) {
+    let changes = client_enterprise
+        .subscribe(SubscriptionFilter::default().feature("f1"))
+        .unwrap();
+
+    // We simulate an update of the configuration:
+    let configuration_snapshot =
+        ConfigurationSnapshot::new("environment_id", configuration_feature1_enabled).unwrap();
+    update_configuration_snapshot(
+        &client_enterprise.state.latest_config_snapshot,
+        &client_enterprise.state.subscriptions,
+        configuration_snapshot,
+    )
+    .unwrap();
+
+    let change = changes.try_recv().unwrap();
+    assert_eq!(change.features.len(), 1);
+    assert_eq!(
+        change.features["f1"],
+        ChangeKind::Modified {
+            value_changed: true,
+            rules_changed: true
+        }
+    );
+    assert!(change.properties.is_empty());
+
+    // Only one notification
+    assert!(changes.try_recv().is_err());
+}
@@ -0,0 +1,115 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use crate::client::AppConfigurationClient;
+use crate::models::tests::{configuration_feature1_enabled, example_configuration_enterprise};
+use crate::models::Configuration;
+use crate::tests::TrivialEntity;
+use crate::{
+    AppConfigurationClientIBMCloud, ChangeKind, ClientOptions, ConnectionState, Feature,
+    ReconnectPolicy, SubscriptionFilter, Value,
+};
+use rstest::*;
+
+use super::fake_transport::FakeTransport;
+
+fn options() -> ClientOptions {
+    ClientOptions {
+        reconnect_policy: ReconnectPolicy {
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(10),
+            multiplier: 1.0,
+            jitter: 0.0,
+        },
+        logging: false,
+        ..Default::default()
+    }
+}
+
+fn client_with(transport: &FakeTransport) -> AppConfigurationClientIBMCloud {
+    AppConfigurationClientIBMCloud::new_with_transport(
+        "apikey",
+        "region",
+        "guid",
+        "environment_id",
+        "collection_id",
+        options(),
+        transport.clone(),
+    )
+    .unwrap()
+}
+
+#[rstest]
+fn test_live_update_through_transport(
+    configuration_feature1_enabled: Configuration,
+    example_configuration_enterprise: Configuration,
+) {
+    let transport = FakeTransport::new(&configuration_feature1_enabled);
+    let client = client_with(&transport);
+    assert_eq!(
+        client.get_connection_state().unwrap(),
+        ConnectionState::Live
+    );
+
+    let feature = client.get_feature_proxy("f1").unwrap();
+    assert_eq!(
+        feature.get_value(&TrivialEntity).unwrap(),
+        Value::Int64(-42)
+    );
+
+    let changes = client
+        .subscribe(SubscriptionFilter::default().feature("f1"))
+        .unwrap();
+
+    // The configuration changes in the server
+    let mut configuration = example_configuration_enterprise;
+    configuration.environments[0].environment_id = "environment_id".to_string();
+    transport.update_configuration(&configuration);
+
+    let change = changes.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(matches!(change.features["f1"], ChangeKind::Modified { .. }));
+    assert_eq!(feature.get_value(&TrivialEntity).unwrap(), Value::Int64(5));
+}
+
+#[rstest]
+fn test_reconnect_through_transport(configuration_feature1_enabled: Configuration) {
+    let transport = FakeTransport::new(&configuration_feature1_enabled);
+    let client = client_with(&transport);
+
+    transport.set_offline(true);
+    let reconnecting = wait_for_state(
+        &client,
+        |state| matches!(state, ConnectionState::Reconnecting { attempt, .. } if *attempt > 0),
+    );
+    assert!(reconnecting);
+
+    transport.set_offline(false);
+    assert!(wait_for_state(&client, |state| state == &ConnectionState::Live));
+}
+
+/// Waits (up to 5 seconds) until the client reaches a state accepted by `condition`.
+fn wait_for_state(
+    client: &AppConfigurationClientIBMCloud,
+    condition: impl Fn(&ConnectionState) -> bool,
+) -> bool {
+    for _ in 0..500 {
+        if condition(&client.get_connection_state().unwrap()) {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    false
+}
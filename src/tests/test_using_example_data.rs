@@ -12,12 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::client::{AppConfigurationClientIBMCloud, AppConfigurationClient};
+use crate::client::{AppConfigurationClient, AppConfigurationClientIBMCloud};
 use crate::tests::TrivialEntity;
 use rstest::*;
 
 use super::client_enterprise;
-use crate::{Feature, Property, Value};
+use crate::{Error, Feature, Property, Value};
 
 #[rstest]
 fn test_get_a_specific_feature(client_enterprise: AppConfigurationClientIBMCloud) {
@@ -42,3 +42,138 @@ fn test_get_a_specific_property(client_enterprise: AppConfigurationClientIBMClou
     assert_eq!(name, "p1");
     assert!(matches!(value, Value::Int64(ref v) if v == &5));
 }
+
+#[rstest]
+fn test_get_value_as(client_enterprise: AppConfigurationClientIBMCloud) {
+    let feature = client_enterprise.get_feature_proxy("f1").unwrap();
+    let value: u8 = feature.get_value_as(&TrivialEntity).unwrap();
+    assert_eq!(value, 5);
+
+    let property = client_enterprise.get_property_proxy("p1").unwrap();
+    let value: f32 = property.get_value_as(&TrivialEntity).unwrap();
+    assert_eq!(value, 5.0);
+
+    let Error::DeserializationError(e) =
+        property.get_value_as::<String>(&TrivialEntity).unwrap_err()
+    else {
+        panic!("Error type mismatch!");
+    };
+    assert_eq!(e.string, "5");
+}
+
+#[rstest]
+fn test_evaluate_all(client_enterprise: AppConfigurationClientIBMCloud) {
+    let entity = crate::tests::GenericEntity {
+        id: "a2".into(),
+        attributes: std::collections::HashMap::from([
+            ("email".into(), Value::from("adi@ibm.com".to_string())),
+            ("number".into(), Value::from(12.0)),
+        ]),
+    };
+    let evaluated = client_enterprise.evaluate_all(&entity).unwrap();
+
+    let mut feature_ids = client_enterprise.get_feature_ids().unwrap();
+    feature_ids.sort();
+    let mut evaluated_ids = evaluated.features.keys().cloned().collect::<Vec<_>>();
+    evaluated_ids.sort();
+    assert_eq!(evaluated_ids, feature_ids);
+    for (feature_id, details) in evaluated.features.iter() {
+        let feature = client_enterprise.get_feature(feature_id).unwrap();
+        assert_eq!(details, &feature.evaluate_details(&entity).unwrap());
+    }
+
+    assert_eq!(
+        evaluated.properties.len(),
+        client_enterprise.get_property_ids().unwrap().len()
+    );
+    for (property_id, value) in evaluated.property_values() {
+        let property = client_enterprise.get_property(&property_id).unwrap();
+        assert_eq!(value, property.get_value(&entity).unwrap());
+    }
+}
+
+#[rstest]
+fn test_evaluate_all_with_errors(client_enterprise: AppConfigurationClientIBMCloud) {
+    // Only segment 'l2dfo8do' checks the 'code' attribute, used by 'f1' and 'p1'.
+    let entity = crate::tests::GenericEntity {
+        id: "a2".into(),
+        attributes: std::collections::HashMap::from([("code".into(), Value::from(true))]),
+    };
+    let evaluated = client_enterprise.evaluate_all(&entity).unwrap();
+
+    assert_eq!(evaluated.feature_errors.len(), 1);
+    assert_eq!(
+        evaluated.feature_errors["f1"].to_string(),
+        client_enterprise
+            .get_feature("f1")
+            .unwrap()
+            .get_value(&entity)
+            .unwrap_err()
+            .to_string()
+    );
+    assert!(!evaluated.features.contains_key("f1"));
+    assert_eq!(
+        evaluated.features.len(),
+        client_enterprise.get_feature_ids().unwrap().len() - 1
+    );
+
+    assert_eq!(
+        evaluated.property_errors.keys().collect::<Vec<_>>(),
+        vec!["p1"]
+    );
+    assert!(!evaluated.properties.contains_key("p1"));
+    assert_eq!(
+        evaluated.properties.len(),
+        client_enterprise.get_property_ids().unwrap().len() - 1
+    );
+}
+
+#[rstest]
+fn test_evaluate_all_records_usage(client_enterprise: AppConfigurationClientIBMCloud) {
+    use crate::client::metering::Metering;
+    use crate::client::transport::ServiceInstance;
+
+    // 'f1' and 'p1' fail to evaluate, they are not recorded
+    let entity = crate::tests::GenericEntity {
+        id: "a2".into(),
+        attributes: std::collections::HashMap::from([("code".into(), Value::from(true))]),
+    };
+    let metering = Metering::default();
+    let snapshot = client_enterprise
+        .state
+        .latest_config_snapshot
+        .lock()
+        .unwrap();
+    snapshot.evaluate_all(&entity, Some(&metering)).unwrap();
+    snapshot
+        .evaluate_bootstrap(&entity, Some(&metering))
+        .unwrap();
+
+    let instance =
+        ServiceInstance::new("region", "guid", "dev", "collection_id", Default::default());
+    let report = metering.take_report(&instance).unwrap().unwrap();
+    let mut usages = report
+        .usages
+        .iter()
+        .map(|u| {
+            (
+                u.feature_id.clone().or(u.property_id.clone()).unwrap(),
+                u.count,
+            )
+        })
+        .collect::<Vec<_>>();
+    usages.sort();
+    assert_eq!(
+        usages,
+        vec![
+            ("f2".to_string(), 2),
+            ("f3".to_string(), 2),
+            ("f4".to_string(), 2),
+            ("f5".to_string(), 2),
+            ("f6".to_string(), 2),
+            ("p2".to_string(), 2),
+            ("p3".to_string(), 2),
+            ("p4".to_string(), 2),
+        ]
+    );
+}
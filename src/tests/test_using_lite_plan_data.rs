@@ -0,0 +1,100 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use crate::client::{AppConfigurationClient, AppConfigurationClientIBMCloud};
+use crate::tests::{GenericEntity, TrivialEntity};
+use rstest::*;
+
+use super::client_lite;
+use crate::{EvaluationReason, Feature, Property, Value};
+
+// Lite plan configurations have no rollout percentages, neither in the features nor
+// in the segment rules.
+
+#[fixture]
+fn ibmer() -> GenericEntity {
+    GenericEntity {
+        id: "a1".into(),
+        attributes: HashMap::from([
+            ("email".into(), Value::from("adi@ibm.com".to_string())),
+            ("number".into(), Value::from(12.0)),
+            ("numbers".into(), Value::from(12.0)),
+        ]),
+    }
+}
+
+#[rstest]
+fn test_get_feature_without_attributes(client_lite: AppConfigurationClientIBMCloud) {
+    let feature = client_lite.get_feature("f1").unwrap();
+
+    let details = feature.evaluate_details(&TrivialEntity).unwrap();
+    assert_eq!(details.value, Value::Int64(5));
+    assert_eq!(details.reason, EvaluationReason::Default);
+    assert_eq!(details.rollout_percentage, Some(100));
+}
+
+#[rstest]
+fn test_get_feature_matching_segment_rule(
+    client_lite: AppConfigurationClientIBMCloud,
+    ibmer: GenericEntity,
+) {
+    let feature = client_lite.get_feature("f1").unwrap();
+
+    let details = feature.evaluate_details(&ibmer).unwrap();
+    assert_eq!(details.value, Value::Int64(25));
+    assert_eq!(details.reason, EvaluationReason::TargetingMatch);
+    assert_eq!(details.rule_order, Some(4));
+    assert_eq!(details.segment_id.as_deref(), Some("l2dfr61d"));
+    assert_eq!(details.rollout_percentage, Some(100));
+
+    let tester = GenericEntity {
+        id: "a2".into(),
+        attributes: HashMap::from([("test".into(), Value::from("testing".to_string()))]),
+    };
+    let value = feature.get_value(&tester).unwrap();
+    assert_eq!(value, Value::Int64(40));
+}
+
+#[rstest]
+fn test_get_feature_matching_segment_rule_with_default_value(
+    client_lite: AppConfigurationClientIBMCloud,
+    ibmer: GenericEntity,
+) {
+    let value = client_lite
+        .get_feature_proxy("f2")
+        .unwrap()
+        .get_value(&ibmer)
+        .unwrap();
+    assert_eq!(value, Value::String("inherited-enabled".into()));
+
+    let value = client_lite
+        .get_feature_proxy("f4")
+        .unwrap()
+        .get_value(&ibmer)
+        .unwrap();
+    assert_eq!(value, Value::Boolean(true));
+}
+
+#[rstest]
+fn test_get_property_matching_segment_rule(
+    client_lite: AppConfigurationClientIBMCloud,
+    ibmer: GenericEntity,
+) {
+    let property = client_lite.get_property_proxy("p1").unwrap();
+
+    assert_eq!(property.get_value(&TrivialEntity).unwrap(), Value::Int64(5));
+    assert_eq!(property.get_value(&ibmer).unwrap(), Value::Int64(444));
+}
@@ -0,0 +1,104 @@
+// (C) Copyright IBM Corp. 2024.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use rstest::*;
+use serde::Deserialize;
+
+use super::client_enterprise;
+use crate::client::cache::ConfigurationSnapshot;
+use crate::client::AppConfigurationClient;
+use crate::models::tests::example_configuration_enterprise;
+use crate::models::{ConfigValue, Configuration, ValueFormat};
+use crate::tests::TrivialEntity;
+use crate::{AppConfigurationClientIBMCloud, Feature, Property, Value};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct PoolSettings {
+    size: u32,
+    hosts: Vec<String>,
+}
+
+/// Client where feature `f2` has a `JSON` value and property `p2` a `YAML` one.
+#[fixture]
+fn client_with_formats(
+    client_enterprise: AppConfigurationClientIBMCloud,
+    mut example_configuration_enterprise: Configuration,
+) -> AppConfigurationClientIBMCloud {
+    let environment = &mut example_configuration_enterprise.environments[0];
+    let feature = environment
+        .features
+        .iter_mut()
+        .find(|f| f.feature_id == "f2")
+        .unwrap();
+    feature.format = Some(ValueFormat::Json);
+    feature.enabled_value = ConfigValue(serde_json::json!(r#"{"size": 4, "hosts": ["a", "b"]}"#));
+    let property = environment
+        .properties
+        .iter_mut()
+        .find(|p| p.property_id == "p2")
+        .unwrap();
+    property.format = Some(ValueFormat::Yaml);
+    property.value = ConfigValue(serde_json::json!("size: 8\nhosts:\n  - c\n"));
+
+    *client_enterprise
+        .state
+        .latest_config_snapshot
+        .lock()
+        .unwrap() = ConfigurationSnapshot::new("dev", example_configuration_enterprise).unwrap();
+    client_enterprise
+}
+
+#[rstest]
+fn test_json_feature(client_with_formats: AppConfigurationClientIBMCloud) {
+    let expected = serde_json::json!({"size": 4, "hosts": ["a", "b"]});
+    let feature = client_with_formats.get_feature("f2").unwrap();
+    assert_eq!(
+        feature.get_value(&TrivialEntity).unwrap(),
+        Value::Structured(expected.clone())
+    );
+    let value: serde_json::Value = feature.get_value_into(&TrivialEntity).unwrap();
+    assert_eq!(value, expected);
+
+    let proxy = client_with_formats.get_feature_proxy("f2").unwrap();
+    let settings: PoolSettings = proxy.get_value_as(&TrivialEntity).unwrap();
+    assert_eq!(
+        settings,
+        PoolSettings {
+            size: 4,
+            hosts: vec!["a".into(), "b".into()]
+        }
+    );
+}
+
+#[rstest]
+fn test_yaml_property(client_with_formats: AppConfigurationClientIBMCloud) {
+    let expected = serde_json::json!({"size": 8, "hosts": ["c"]});
+    let property = client_with_formats.get_property("p2").unwrap();
+    assert_eq!(
+        property.get_value(&TrivialEntity).unwrap(),
+        Value::Structured(expected.clone())
+    );
+    let value: serde_json::Value = property.get_value_into(&TrivialEntity).unwrap();
+    assert_eq!(value, expected);
+
+    let proxy = client_with_formats.get_property_proxy("p2").unwrap();
+    let settings: PoolSettings = proxy.get_value_as(&TrivialEntity).unwrap();
+    assert_eq!(
+        settings,
+        PoolSettings {
+            size: 8,
+            hosts: vec!["c".into()]
+        }
+    );
+}
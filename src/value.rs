@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::errors::DeserializationError;
 use crate::Error;
 
 /// A wrapper on top of the primitive types acepted by the library.
@@ -22,6 +26,67 @@ pub enum Value {
     Int64(i64),
     String(String),
     Boolean(bool),
+    /// Structured data, from `STRING` values with `JSON` or `YAML` format.
+    Structured(serde_json::Value),
+}
+
+impl Value {
+    /// Deserializes the value into `T` as if it were the equivalent JSON value.
+    pub(crate) fn deserialize_into<T: DeserializeOwned>(self) -> crate::Result<T> {
+        let data = self.into_json();
+        T::deserialize(&data).map_err(|e| {
+            let string = match data {
+                serde_json::Value::String(string) => string,
+                data => data.to_string(),
+            };
+            DeserializationError {
+                string,
+                source: e.into(),
+            }
+            .into()
+        })
+    }
+
+    fn into_json(self) -> serde_json::Value {
+        match self {
+            Value::Float64(v) => serde_json::Number::from_f64(v)
+                .map_or(serde_json::Value::Null, serde_json::Value::Number),
+            Value::UInt64(v) => v.into(),
+            Value::Int64(v) => v.into(),
+            Value::String(v) => v.into(),
+            Value::Boolean(v) => v.into(),
+            Value::Structured(v) => v,
+        }
+    }
+}
+
+// Values are serialized as plain JSON values, so they can be consumed by any JSON reader.
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.clone().into_json().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = serde_json::Value::deserialize(deserializer)?;
+        Ok(match data {
+            serde_json::Value::Bool(v) => Value::Boolean(v),
+            serde_json::Value::String(v) => Value::String(v),
+            serde_json::Value::Number(ref n) => {
+                if let Some(v) = n.as_i64() {
+                    Value::Int64(v)
+                } else if let Some(v) = n.as_u64() {
+                    Value::UInt64(v)
+                } else if let Some(v) = n.as_f64() {
+                    Value::Float64(v)
+                } else {
+                    Value::Structured(data)
+                }
+            }
+            data => Value::Structured(data),
+        })
+    }
 }
 
 impl From<f64> for Value {
@@ -111,6 +176,17 @@ impl TryFrom<Value> for bool {
     }
 }
 
+impl TryFrom<Value> for serde_json::Value {
+    type Error = crate::Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Structured(f) => Ok(f),
+            _ => Err(Error::MismatchType),
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
 
@@ -124,10 +200,22 @@ pub mod tests {
         let as_f64: f64 = value.clone().try_into().unwrap();
         assert_eq!(as_f64, 42f64);
 
-        assert!(matches!(TryInto::<u64>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-        assert!(matches!(TryInto::<i64>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-        assert!(matches!(TryInto::<String>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-        assert!(matches!(TryInto::<bool>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
+        assert!(matches!(
+            TryInto::<u64>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
+        assert!(matches!(
+            TryInto::<i64>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
+        assert!(matches!(
+            TryInto::<String>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
+        assert!(matches!(
+            TryInto::<bool>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
     }
 
     #[test]
@@ -136,22 +224,34 @@ pub mod tests {
         {
             let value = Value::from(42u64);
             assert!(matches!(value, Value::UInt64(ref v) if v == &42u64));
-    
+
             let as_u64: u64 = value.clone().try_into().unwrap();
             assert_eq!(as_u64, 42u64);
 
             let as_i64: i64 = value.clone().try_into().unwrap();
             assert_eq!(as_i64, 42i64);
 
-            assert!(matches!(TryInto::<f64>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-            assert!(matches!(TryInto::<String>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-            assert!(matches!(TryInto::<bool>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
+            assert!(matches!(
+                TryInto::<f64>::try_into(value.clone()).unwrap_err(),
+                Error::MismatchType
+            ));
+            assert!(matches!(
+                TryInto::<String>::try_into(value.clone()).unwrap_err(),
+                Error::MismatchType
+            ));
+            assert!(matches!(
+                TryInto::<bool>::try_into(value.clone()).unwrap_err(),
+                Error::MismatchType
+            ));
         }
 
         // An u64 outside the range of i64
         {
             let value = Value::from(u64::MAX);
-            assert!(matches!(TryInto::<i64>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
+            assert!(matches!(
+                TryInto::<i64>::try_into(value.clone()).unwrap_err(),
+                Error::MismatchType
+            ));
         }
     }
 
@@ -161,25 +261,37 @@ pub mod tests {
         {
             let value = Value::from(42i64);
             assert!(matches!(value, Value::Int64(ref v) if v == &42i64));
-    
+
             let as_i64: i64 = value.clone().try_into().unwrap();
             assert_eq!(as_i64, 42i64);
 
             let as_u64: u64 = value.clone().try_into().unwrap();
             assert_eq!(as_u64, 42u64);
 
-            assert!(matches!(TryInto::<f64>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-            assert!(matches!(TryInto::<String>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-            assert!(matches!(TryInto::<bool>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
+            assert!(matches!(
+                TryInto::<f64>::try_into(value.clone()).unwrap_err(),
+                Error::MismatchType
+            ));
+            assert!(matches!(
+                TryInto::<String>::try_into(value.clone()).unwrap_err(),
+                Error::MismatchType
+            ));
+            assert!(matches!(
+                TryInto::<bool>::try_into(value.clone()).unwrap_err(),
+                Error::MismatchType
+            ));
         }
 
         // An i64 outside the range of u64
         {
             let value = Value::from(-2i64);
-            assert!(matches!(TryInto::<u64>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
+            assert!(matches!(
+                TryInto::<u64>::try_into(value.clone()).unwrap_err(),
+                Error::MismatchType
+            ));
         }
     }
-    
+
     #[test]
     fn test_string() {
         let value = Value::from("value".to_string());
@@ -188,10 +300,22 @@ pub mod tests {
         let as_string: String = value.clone().try_into().unwrap();
         assert_eq!(as_string, "value");
 
-        assert!(matches!(TryInto::<f64>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-        assert!(matches!(TryInto::<u64>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-        assert!(matches!(TryInto::<i64>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-        assert!(matches!(TryInto::<bool>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
+        assert!(matches!(
+            TryInto::<f64>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
+        assert!(matches!(
+            TryInto::<u64>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
+        assert!(matches!(
+            TryInto::<i64>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
+        assert!(matches!(
+            TryInto::<bool>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
     }
 
     #[test]
@@ -203,9 +327,112 @@ pub mod tests {
         let as_boolean: bool = value.clone().try_into().unwrap();
         assert_eq!(as_boolean, false);
 
-        assert!(matches!(TryInto::<f64>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-        assert!(matches!(TryInto::<u64>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-        assert!(matches!(TryInto::<i64>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
-        assert!(matches!(TryInto::<String>::try_into(value.clone()).unwrap_err(), Error::MismatchType));
+        assert!(matches!(
+            TryInto::<f64>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
+        assert!(matches!(
+            TryInto::<u64>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
+        assert!(matches!(
+            TryInto::<i64>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
+        assert!(matches!(
+            TryInto::<String>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
+    }
+
+    #[test]
+    fn test_structured() {
+        let value = Value::Structured(serde_json::json!({"key": "value"}));
+
+        let as_json: serde_json::Value = value.clone().try_into().unwrap();
+        assert_eq!(as_json, serde_json::json!({"key": "value"}));
+
+        assert!(matches!(
+            TryInto::<String>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
+        assert!(matches!(
+            TryInto::<bool>::try_into(value.clone()).unwrap_err(),
+            Error::MismatchType
+        ));
+        assert!(matches!(
+            TryInto::<serde_json::Value>::try_into(Value::from(true)).unwrap_err(),
+            Error::MismatchType
+        ));
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct PoolSettings {
+        size: u32,
+        name: Option<String>,
+    }
+
+    #[test]
+    fn test_deserialize_into() {
+        let value = Value::Structured(serde_json::json!({"size": 4}));
+        let settings: PoolSettings = value.deserialize_into().unwrap();
+        assert_eq!(
+            settings,
+            PoolSettings {
+                size: 4,
+                name: None
+            }
+        );
+
+        assert_eq!(Value::from(42u64).deserialize_into::<u8>().unwrap(), 42);
+        assert_eq!(Value::from(-42i64).deserialize_into::<i32>().unwrap(), -42);
+        assert_eq!(Value::from(0.5).deserialize_into::<f32>().unwrap(), 0.5);
+        assert!(Value::from(true).deserialize_into::<bool>().unwrap());
+        assert_eq!(
+            Value::from("value".to_string())
+                .deserialize_into::<String>()
+                .unwrap(),
+            "value"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_into_error() {
+        let value = Value::Structured(serde_json::json!({"size": "four"}));
+        let Error::DeserializationError(e) = value.deserialize_into::<PoolSettings>().unwrap_err()
+        else {
+            panic!("Error type mismatch!");
+        };
+        assert_eq!(e.string, r#"{"size":"four"}"#);
+
+        // Strings are reported as they are, without quotes
+        let value = Value::from("four".to_string());
+        let Error::DeserializationError(e) = value.deserialize_into::<u32>().unwrap_err() else {
+            panic!("Error type mismatch!");
+        };
+        assert_eq!(e.string, "four");
+
+        let value = Value::from(-1i64);
+        assert!(value.deserialize_into::<u32>().is_err());
+    }
+
+    #[test]
+    fn test_serde() {
+        let values = [
+            Value::from(0.5),
+            Value::from(u64::MAX),
+            Value::from(-42i64),
+            Value::from("value".to_string()),
+            Value::from(true),
+            Value::Structured(serde_json::json!({"key": [1, 2]})),
+        ];
+        let json = serde_json::to_string(&values).unwrap();
+        assert_eq!(
+            json,
+            r#"[0.5,18446744073709551615,-42,"value",true,{"key":[1,2]}]"#
+        );
+
+        let deserialized: Vec<Value> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, values);
     }
 }